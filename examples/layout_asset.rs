@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use bevy_touch_stick::{prelude::*, TouchLayoutRoot};
+
+// ID for joysticks
+#[derive(Default, Reflect, Hash, Clone, PartialEq, Eq, Debug)]
+enum Stick {
+    #[default]
+    Left,
+    Right,
+}
+
+fn main() {
+    App::new()
+        .add_plugins((
+            // edit assets/sticks.touch_layout.ron while the example is running
+            DefaultPlugins.set(AssetPlugin {
+                watch_for_changes_override: Some(true),
+                ..default()
+            }),
+            TouchStickPlugin::<Stick>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, log_sticks)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2dBundle::default());
+
+    // the sticks are spawned as children of this node once the layout is loaded
+    commands.spawn((
+        TouchLayoutRoot::new(asset_server.load("sticks.touch_layout.ron"))
+            .with_id("Left", Stick::Left)
+            .with_id("Right", Stick::Right),
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+}
+
+fn log_sticks(sticks: Query<&TouchStick<Stick>>) {
+    for stick in &sticks {
+        if stick.value != Vec2::ZERO {
+            info!("{:?}: {}", stick.id, stick.value);
+        }
+    }
+}
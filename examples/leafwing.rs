@@ -30,6 +30,8 @@ fn main() {
             TouchStickPlugin::<Stick>::default(),
             // add leafwing plugin
             InputManagerPlugin::<Action>::default(),
+            // feed touch sticks into leafwing actions
+            TouchStickActionPlugin::<Action, Stick>::default(),
         ))
         .add_systems(Startup, setup)
         .add_systems(Update, move_player)
@@ -60,6 +62,11 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                     .insert(Action::Look, DualAxis::right_stick())
                     .build(),
             },
+            // Describes which touch sticks drive which actions
+            TouchStickInputMap::<Action, Stick>::default()
+                .insert(Action::Move, Stick::Left)
+                .insert(Action::Look, Stick::Right)
+                .build(),
             SpriteBundle {
                 transform: Transform {
                     translation: Vec3::new(0., 0., 0.),
@@ -92,28 +99,23 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 
     // spawn a move stick
     commands
-        .spawn((
-            // map this stick as a left gamepad stick (through bevy_input)
-            // leafwing will register this as a normal gamepad
-            TouchStickGamepadMapping::LEFT_STICK,
-            TouchStickUiBundle {
-                stick: TouchStick {
-                    id: Stick::Left,
-                    stick_type: TouchStickType::Fixed,
-                    ..default()
-                },
-                // configure the interactable area through bevy_ui
-                style: Style {
-                    width: Val::Px(150.),
-                    height: Val::Px(150.),
-                    position_type: PositionType::Absolute,
-                    left: Val::Percent(15.),
-                    bottom: Val::Percent(5.),
-                    ..default()
-                },
+        .spawn(TouchStickUiBundle {
+            stick: TouchStick {
+                id: Stick::Left,
+                stick_type: TouchStickType::Fixed,
                 ..default()
             },
-        ))
+            // configure the interactable area through bevy_ui
+            style: Style {
+                width: Val::Px(150.),
+                height: Val::Px(150.),
+                position_type: PositionType::Absolute,
+                left: Val::Percent(15.),
+                bottom: Val::Percent(5.),
+                ..default()
+            },
+            ..default()
+        })
         .with_children(|parent| {
             parent.spawn((
                 TouchStickUiKnob,
@@ -143,28 +145,23 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 
     // spawn a look stick
     commands
-        .spawn((
-            // map this stick as a right gamepad stick (through bevy_input)
-            // leafwing will register this as a normal gamepad
-            TouchStickGamepadMapping::RIGHT_STICK,
-            TouchStickUiBundle {
-                stick: TouchStick {
-                    id: Stick::Right,
-                    stick_type: TouchStickType::Floating,
-                    ..default()
-                },
-                // configure the interactable area through bevy_ui
-                style: Style {
-                    width: Val::Px(150.),
-                    height: Val::Px(150.),
-                    position_type: PositionType::Absolute,
-                    right: Val::Percent(15.),
-                    bottom: Val::Percent(5.),
-                    ..default()
-                },
+        .spawn(TouchStickUiBundle {
+            stick: TouchStick {
+                id: Stick::Right,
+                stick_type: TouchStickType::Floating,
                 ..default()
             },
-        ))
+            // configure the interactable area through bevy_ui
+            style: Style {
+                width: Val::Px(150.),
+                height: Val::Px(150.),
+                position_type: PositionType::Absolute,
+                right: Val::Percent(15.),
+                bottom: Val::Percent(5.),
+                ..default()
+            },
+            ..default()
+        })
         .with_children(|parent| {
             parent.spawn((
                 TouchStickUiKnob,
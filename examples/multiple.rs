@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
-use bevy_touch_stick::{prelude::*, TouchStickUiKnob, TouchStickUiOutline};
+use bevy_touch_stick::{
+    prelude::*, TouchStickKeyboardEmulation, TouchStickUiKnob, TouchStickUiOutline,
+};
 use std::f32::consts::PI;
 
 // ID for joysticks
@@ -85,6 +87,8 @@ fn create_scene(mut commands: Commands, asset_server: Res<AssetServer>) {
             parent
                 .spawn((
                     Name::new("LeftTouchStick"),
+                    // drive the stick with WASD when testing on desktop
+                    TouchStickKeyboardEmulation::wasd(),
                     TouchStickUiBundle {
                         stick: TouchStick {
                             id: Stick::Left,
@@ -132,6 +136,7 @@ fn create_scene(mut commands: Commands, asset_server: Res<AssetServer>) {
             parent
                 .spawn((
                     Name::new("RightTouchStick"),
+                    TouchStickKeyboardEmulation::arrows(),
                     TouchStickUiBundle {
                         stick: TouchStick {
                             id: Stick::Right,
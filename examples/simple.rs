@@ -2,10 +2,6 @@ use bevy::prelude::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_touch_stick::{prelude::*, TouchStickUiKnob, TouchStickUiOutline};
 
-/// Marker type for our touch stick
-#[derive(Default, Reflect, Hash, Clone, PartialEq, Eq)]
-struct MyStick;
-
 fn main() {
     App::new()
         .insert_resource(ClearColor(Color::BLACK))
@@ -13,8 +9,8 @@ fn main() {
             DefaultPlugins,
             // add an inspector for easily changing settings at runtime
             WorldInspectorPlugin::default(),
-            // add the plugin
-            TouchStickPlugin::<MyStick>::default(),
+            // add the plugin, we only have one stick, so we don't need an id type
+            TouchStickPlugin::<()>::default(),
         ))
         .add_systems(Startup, setup)
         .add_systems(Update, move_player)
@@ -46,8 +42,8 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 
     // spawn a touch stick
     commands
-        .spawn(TouchStickUiBundle::<MyStick> {
-            stick: TouchStick::<MyStick> {
+        .spawn(TouchStickUiBundle::<()> {
+            stick: TouchStick {
                 radius: 75.0,
                 ..default()
             },
@@ -60,7 +56,6 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                     right: Val::Auto,
                     top: Val::Auto,
                     bottom: Val::Vh(15.),
-                    ..default()
                 },
                 ..default()
             },
@@ -95,7 +90,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 }
 
 fn move_player(
-    sticks: Query<&TouchStick<MyStick>>,
+    sticks: Query<&TouchStick>,
     mut players: Query<(&mut Transform, &Player)>,
     time: Res<Time>,
 ) {
@@ -2,13 +2,19 @@ use std::marker::PhantomData;
 
 use bevy::{
     input::gamepad::{
-        GamepadAxisChangedEvent, GamepadConnection, GamepadConnectionEvent, GamepadEvent,
-        GamepadInfo,
+        GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnection,
+        GamepadConnectionEvent, GamepadEvent, GamepadInfo,
     },
     prelude::*,
 };
 
-use crate::{StickIdType, TouchStick};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    triggers::update_stick_triggers, StickIdType, TouchStick, TouchStickLayoutMirror,
+    TouchStickSystem, TouchStickTriggers,
+};
 
 /// Plugin that makes [`TouchStick`]s pretend to be regular bevy gamepads
 ///
@@ -27,16 +33,31 @@ impl<S: StickIdType> Plugin for GamepadMappingPlugin<S> {
     fn build(&self, app: &mut App) {
         app.add_systems(
             PreUpdate,
-            (connect_gamepad::<S>, send_axis_events::<S>).chain(),
+            (
+                send_axis_events::<S>,
+                send_pressure_events::<S>,
+                send_trigger_button_events::<S>.after(update_stick_triggers::<S>),
+            )
+                .after(connect_gamepad)
+                .after(TouchStickSystem::UpdateSticks),
         );
     }
 }
 
+/// Connects the emulated gamepad, shared between all [`TouchStick`] id types
+pub(crate) struct GamepadConnectionPlugin;
+
+impl Plugin for GamepadConnectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, connect_gamepad);
+    }
+}
+
 /// HACK: chosen at random, we're betting on no collisions with gilrs gamepads
 /// needs to be below `u32::MAX` to work on 32bit platforms.
 const TOUCH_GAMEPAD_ID: usize = 3407632091;
 
-const TOUCH_GAMEPAD: Gamepad = Gamepad {
+pub(crate) const TOUCH_GAMEPAD: Gamepad = Gamepad {
     id: TOUCH_GAMEPAD_ID,
 };
 
@@ -44,6 +65,7 @@ const TOUCH_GAMEPAD: Gamepad = Gamepad {
 ///
 /// Adding this component to a [`TouchStick`] will create an emulated gamepad through `bevy_input`.
 #[derive(Component, Reflect, Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TouchStickGamepadMapping(pub GamepadAxisType, pub GamepadAxisType);
 
 impl TouchStickGamepadMapping {
@@ -53,15 +75,85 @@ impl TouchStickGamepadMapping {
     /// Defines default right stick mapping
     pub const RIGHT_STICK: Self =
         TouchStickGamepadMapping(GamepadAxisType::RightStickX, GamepadAxisType::RightStickY);
+
+    /// Returns the mapping with left and right axes swapped
+    pub fn mirrored(self) -> Self {
+        fn mirror(axis: GamepadAxisType) -> GamepadAxisType {
+            match axis {
+                GamepadAxisType::LeftStickX => GamepadAxisType::RightStickX,
+                GamepadAxisType::LeftStickY => GamepadAxisType::RightStickY,
+                GamepadAxisType::LeftZ => GamepadAxisType::RightZ,
+                GamepadAxisType::RightStickX => GamepadAxisType::LeftStickX,
+                GamepadAxisType::RightStickY => GamepadAxisType::LeftStickY,
+                GamepadAxisType::RightZ => GamepadAxisType::LeftZ,
+                other => other,
+            }
+        }
+        TouchStickGamepadMapping(mirror(self.0), mirror(self.1))
+    }
 }
 
-/// The gamepad is connected when the first [`TouchStick`] is added.
-fn connect_gamepad<S: StickIdType>(
+/// Mapping of [`TouchStick::pressure`] to an analog bevy gamepad button, e.g. a trigger
+///
+/// Adding this component to a [`TouchStick`] will create an emulated gamepad
+/// through `bevy_input`. The button is pressed and released according to the
+/// gamepad's `ButtonSettings`, so pressing harder can fire.
+#[derive(Component, Reflect, Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickGamepadPressureMapping(pub GamepadButtonType);
+
+impl TouchStickGamepadPressureMapping {
+    /// Defines default left trigger mapping
+    pub const LEFT_TRIGGER: Self =
+        TouchStickGamepadPressureMapping(GamepadButtonType::LeftTrigger2);
+    /// Defines default right trigger mapping
+    pub const RIGHT_TRIGGER: Self =
+        TouchStickGamepadPressureMapping(GamepadButtonType::RightTrigger2);
+
+    /// Returns the mapping with left and right triggers swapped
+    pub fn mirrored(self) -> Self {
+        TouchStickGamepadPressureMapping(mirror_button(self.0))
+    }
+}
+
+/// Swaps left and right triggers
+fn mirror_button(button: GamepadButtonType) -> GamepadButtonType {
+    match button {
+        GamepadButtonType::LeftTrigger => GamepadButtonType::RightTrigger,
+        GamepadButtonType::LeftTrigger2 => GamepadButtonType::RightTrigger2,
+        GamepadButtonType::RightTrigger => GamepadButtonType::LeftTrigger,
+        GamepadButtonType::RightTrigger2 => GamepadButtonType::LeftTrigger2,
+        other => other,
+    }
+}
+
+/// The gamepad is connected when the first mapped [`TouchStick`] is added.
+#[allow(clippy::type_complexity)]
+fn connect_gamepad(
     mut gamepad_events: EventWriter<GamepadEvent>,
-    sticks: Query<(), (With<TouchStick<S>>, With<TouchStickGamepadMapping>)>,
+    sticks: Query<
+        (
+            Has<TouchStickGamepadMapping>,
+            Has<TouchStickGamepadPressureMapping>,
+            Option<&TouchStickTriggers>,
+        ),
+        Or<(
+            With<TouchStickGamepadMapping>,
+            With<TouchStickGamepadPressureMapping>,
+            With<TouchStickTriggers>,
+        )>,
+    >,
     mut was_connected: Local<bool>,
 ) {
-    let connected = !sticks.is_empty();
+    let connected = sticks.iter().any(|(axes, pressure, triggers)| {
+        axes || pressure
+            || triggers.is_some_and(|triggers| {
+                triggers
+                    .zones
+                    .iter()
+                    .any(|zone| zone.gamepad_button.is_some())
+            })
+    });
 
     if *was_connected != connected {
         *was_connected = connected;
@@ -85,9 +177,15 @@ fn connect_gamepad<S: StickIdType>(
 fn send_axis_events<S: StickIdType>(
     mut events: EventWriter<GamepadEvent>,
     sticks: Query<(&TouchStick<S>, &TouchStickGamepadMapping)>,
+    mirror: Res<TouchStickLayoutMirror>,
 ) {
     for (stick, axis_mapping) in &sticks {
         let gamepad = TOUCH_GAMEPAD;
+        let axis_mapping = if mirror.swaps_gamepad_mappings() {
+            axis_mapping.mirrored()
+        } else {
+            *axis_mapping
+        };
         let TouchStickGamepadMapping(x_type, y_type) = axis_mapping;
         let Vec2 { x, y } = stick.value;
         trace!("sending axis event {x}, {y}");
@@ -100,7 +198,53 @@ fn send_axis_events<S: StickIdType>(
         // events.send(GamepadAxisChangedEvent::new(gamepad, axis_type, filtered_value).into());
         // }
 
-        events.send(GamepadAxisChangedEvent::new(gamepad, *x_type, x).into());
-        events.send(GamepadAxisChangedEvent::new(gamepad, *y_type, y).into());
+        events.send(GamepadAxisChangedEvent::new(gamepad, x_type, x).into());
+        events.send(GamepadAxisChangedEvent::new(gamepad, y_type, y).into());
+    }
+}
+
+/// Reads pressure from touch sticks and sends it as bevy button events
+///
+/// Like `bevy_gilrs`, the analog value is written to `Axis<GamepadButton>`
+/// directly, since bevy only derives the pressed state from the events.
+fn send_pressure_events<S: StickIdType>(
+    mut events: EventWriter<GamepadEvent>,
+    mut button_axes: ResMut<Axis<GamepadButton>>,
+    sticks: Query<(&TouchStick<S>, &TouchStickGamepadPressureMapping)>,
+    mirror: Res<TouchStickLayoutMirror>,
+) {
+    for (stick, button_mapping) in &sticks {
+        let button_mapping = if mirror.swaps_gamepad_mappings() {
+            button_mapping.mirrored()
+        } else {
+            *button_mapping
+        };
+        let value = stick.pressure.unwrap_or_default();
+        button_axes.set(GamepadButton::new(TOUCH_GAMEPAD, button_mapping.0), value);
+        events.send(GamepadButtonChangedEvent::new(TOUCH_GAMEPAD, button_mapping.0, value).into());
+    }
+}
+
+/// Presses gamepad buttons mapped to [`TouchStickTriggers`] zones
+fn send_trigger_button_events<S: StickIdType>(
+    mut events: EventWriter<GamepadEvent>,
+    mut button_axes: ResMut<Axis<GamepadButton>>,
+    sticks: Query<&TouchStickTriggers, With<TouchStick<S>>>,
+    mirror: Res<TouchStickLayoutMirror>,
+) {
+    for triggers in &sticks {
+        for zone in &triggers.zones {
+            let Some(button) = zone.gamepad_button else {
+                continue;
+            };
+            let button = if mirror.swaps_gamepad_mappings() {
+                mirror_button(button)
+            } else {
+                button
+            };
+            let value = if zone.pressed() { 1. } else { 0. };
+            button_axes.set(GamepadButton::new(TOUCH_GAMEPAD, button), value);
+            events.send(GamepadButtonChangedEvent::new(TOUCH_GAMEPAD, button, value).into());
+        }
     }
 }
@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input::{DragEvent, EMULATED_DRAG_ID},
+    StickIdType, TouchStick,
+};
+
+/// Recognizes two-finger gestures in the zone of a [`TouchStick`]
+///
+/// Add to a [`TouchStick`]. One finger drives the stick as usual. When a
+/// second finger touches the zone, the stick is released and the two fingers
+/// send [`TouchStickPinchEvent`]s and [`TouchStickRotateEvent`]s instead. The
+/// stick stays released until all fingers are lifted, or another second finger
+/// starts a new gesture.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickGestures {
+    /// Send [`TouchStickPinchEvent`]s
+    pub pinch: bool,
+    /// Send [`TouchStickRotateEvent`]s
+    pub rotate: bool,
+    /// Drag ids and positions of the fingers in the current gesture
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    touches: Vec<(u64, Vec2)>,
+}
+
+impl Default for TouchStickGestures {
+    fn default() -> Self {
+        Self {
+            pinch: true,
+            rotate: true,
+            touches: default(),
+        }
+    }
+}
+
+impl TouchStickGestures {
+    /// Whether fingers of a gesture are still touching the zone
+    pub fn is_active(&self) -> bool {
+        !self.touches.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.touches.clear();
+    }
+}
+
+/// Sent when the two fingers of a [`TouchStickGestures`] move apart or together
+#[derive(Event, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickPinchEvent<S: StickIdType = ()> {
+    /// Id of the [`TouchStick`]
+    pub id: S,
+    /// Entity holding the [`TouchStick`]
+    pub entity: Entity,
+    /// Ratio of the new and previous finger distance, above 1 when spreading
+    pub scale_delta: f32,
+}
+
+/// Sent when the two fingers of a [`TouchStickGestures`] rotate around each other
+#[derive(Event, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickRotateEvent<S: StickIdType = ()> {
+    /// Id of the [`TouchStick`]
+    pub id: S,
+    /// Entity holding the [`TouchStick`]
+    pub entity: Entity,
+    /// Rotation since the previous event in radians, counter-clockwise on screen
+    pub angle_delta: f32,
+}
+
+/// Result of passing a [`DragEvent`] to [`handle_gesture_event`]
+pub(crate) enum GestureInput {
+    /// The event is not part of a gesture
+    Ignored,
+    /// The event is part of a gesture
+    Consumed,
+    /// A gesture started, the stick should be released
+    Started,
+}
+
+/// Tracks the fingers of a gesture and sends events when they move
+pub(crate) fn handle_gesture_event<S: StickIdType>(
+    gestures: &mut TouchStickGestures,
+    entity: Entity,
+    stick: &TouchStick<S>,
+    event: &DragEvent,
+    pinch_events: &mut EventWriter<TouchStickPinchEvent<S>>,
+    rotate_events: &mut EventWriter<TouchStickRotateEvent<S>>,
+) -> GestureInput {
+    // emulated drags are a single pointer and can't be part of a gesture
+    if event.is_emulated() {
+        return GestureInput::Ignored;
+    }
+    match *event {
+        DragEvent::Start { id, position, .. } if stick.interactable_zone.contains(position) => {
+            if gestures.touches.len() == 1 {
+                gestures.touches.push((id, position));
+                GestureInput::Consumed
+            } else if gestures.is_active() {
+                // ignore additional fingers
+                GestureInput::Consumed
+            } else if let Some(first) = stick
+                .drag_id
+                .filter(|first| *first != id && *first & EMULATED_DRAG_ID == 0)
+            {
+                gestures.touches = vec![(first, stick.drag_position), (id, position)];
+                GestureInput::Started
+            } else {
+                GestureInput::Ignored
+            }
+        }
+        DragEvent::Drag { id, position, .. } => {
+            let Some(index) = gestures.touches.iter().position(|(touch, _)| *touch == id) else {
+                return GestureInput::Ignored;
+            };
+            let previous = finger_offset(&gestures.touches);
+            gestures.touches[index].1 = position;
+            let current = finger_offset(&gestures.touches);
+
+            if let (Some(previous), Some(current)) = (previous, current) {
+                if gestures.pinch && current.length() != previous.length() {
+                    pinch_events.send(TouchStickPinchEvent {
+                        id: stick.id.clone(),
+                        entity,
+                        scale_delta: current.length() / previous.length(),
+                    });
+                }
+                // input events are y positive down, so we flip it
+                let angle_delta = -previous.angle_between(current);
+                if gestures.rotate && angle_delta != 0. {
+                    rotate_events.send(TouchStickRotateEvent {
+                        id: stick.id.clone(),
+                        entity,
+                        angle_delta,
+                    });
+                }
+            }
+            GestureInput::Consumed
+        }
+        DragEvent::End { id } => {
+            let Some(index) = gestures.touches.iter().position(|(touch, _)| *touch == id) else {
+                return GestureInput::Ignored;
+            };
+            gestures.touches.remove(index);
+            GestureInput::Consumed
+        }
+        _ => GestureInput::Ignored,
+    }
+}
+
+/// Offset between the two fingers of a gesture, if there are two fingers apart
+fn finger_offset(touches: &[(u64, Vec2)]) -> Option<Vec2> {
+    let [(_, first), (_, second)] = touches else {
+        return None;
+    };
+    Some(*second - *first).filter(|offset| offset.length() > f32::EPSILON)
+}
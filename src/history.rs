@@ -0,0 +1,152 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{StickIdType, TouchStick};
+
+/// Records recent values of a [`TouchStick`], e.g. for aim assist
+///
+/// Add to a [`TouchStick`]. Once per frame, after the stick is updated, its
+/// value is pushed with the current real time, dropping the oldest sample when
+/// `capacity` is reached. Threshold queries compare the two latest samples,
+/// so like [`ButtonInput::just_pressed`] they are true for a single frame.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct TouchStickHistory {
+    /// Maximum number of samples to keep
+    pub capacity: usize,
+    #[reflect(ignore)]
+    samples: VecDeque<(Duration, Vec2)>,
+    #[reflect(ignore)]
+    pressed_at: Option<Duration>,
+}
+
+impl Default for TouchStickHistory {
+    fn default() -> Self {
+        Self::with_capacity(16)
+    }
+}
+
+impl TouchStickHistory {
+    /// Keeps up to `capacity` samples, at least two
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(2);
+        Self {
+            capacity,
+            samples: VecDeque::with_capacity(capacity),
+            pressed_at: None,
+        }
+    }
+
+    /// Recorded samples of elapsed real time and value, oldest first
+    pub fn samples(&self) -> impl DoubleEndedIterator<Item = (Duration, Vec2)> + '_ {
+        self.samples.iter().copied()
+    }
+
+    /// The latest recorded value
+    pub fn value(&self) -> Vec2 {
+        self.latest().map(|(_, value)| value).unwrap_or_default()
+    }
+
+    /// The value recorded the frame before the latest one
+    pub fn previous_value(&self) -> Vec2 {
+        self.previous().map(|(_, value)| value).unwrap_or_default()
+    }
+
+    /// Change of the value per second between the two latest samples
+    pub fn velocity(&self) -> Vec2 {
+        let (Some((previous_time, previous)), Some((time, value))) =
+            (self.previous(), self.latest())
+        else {
+            return Vec2::ZERO;
+        };
+        let seconds = (time - previous_time).as_secs_f32();
+        if seconds <= 0. {
+            return Vec2::ZERO;
+        }
+        (value - previous) / seconds
+    }
+
+    /// Average change of the value per second over the whole history
+    pub fn average_velocity(&self) -> Vec2 {
+        let (Some(&(first_time, first)), Some((time, value))) =
+            (self.samples.front(), self.latest())
+        else {
+            return Vec2::ZERO;
+        };
+        let seconds = (time - first_time).as_secs_f32();
+        if seconds <= 0. {
+            return Vec2::ZERO;
+        }
+        (value - first) / seconds
+    }
+
+    /// Rotation of the value per second between the two latest samples
+    ///
+    /// In radians, counter-clockwise positive. Zero while either value is zero.
+    pub fn angular_velocity(&self) -> f32 {
+        let (Some((previous_time, previous)), Some((time, value))) =
+            (self.previous(), self.latest())
+        else {
+            return 0.;
+        };
+        let seconds = (time - previous_time).as_secs_f32();
+        if seconds <= 0. || previous == Vec2::ZERO || value == Vec2::ZERO {
+            return 0.;
+        }
+        previous.angle_between(value) / seconds
+    }
+
+    /// Time the stick has been pressed at the latest sample, `None` if released
+    pub fn time_since_press(&self) -> Option<Duration> {
+        let pressed_at = self.pressed_at?;
+        let (time, _) = self.latest()?;
+        Some(time.saturating_sub(pressed_at))
+    }
+
+    /// Whether the length of the value reached `threshold` in the latest frame
+    pub fn just_crossed_above(&self, threshold: f32) -> bool {
+        self.previous_value().length() < threshold && self.value().length() >= threshold
+    }
+
+    /// Whether the length of the value dropped below `threshold` in the latest frame
+    pub fn just_crossed_below(&self, threshold: f32) -> bool {
+        self.previous_value().length() >= threshold && self.value().length() < threshold
+    }
+
+    /// Forgets all samples
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.pressed_at = None;
+    }
+
+    fn latest(&self) -> Option<(Duration, Vec2)> {
+        self.samples.back().copied()
+    }
+
+    fn previous(&self) -> Option<(Duration, Vec2)> {
+        self.samples.iter().rev().nth(1).copied()
+    }
+
+    fn push(&mut self, time: Duration, value: Vec2, pressed: bool) {
+        match (pressed, self.pressed_at) {
+            (true, None) => self.pressed_at = Some(time),
+            (false, Some(_)) => self.pressed_at = None,
+            _ => {}
+        }
+        while self.samples.len() >= self.capacity.max(2) {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((time, value));
+    }
+}
+
+pub(crate) fn record_stick_history<S: StickIdType>(
+    time: Res<Time<Real>>,
+    mut sticks: Query<(&TouchStick<S>, &mut TouchStickHistory)>,
+) {
+    let now = time.elapsed();
+    for (stick, mut history) in &mut sticks {
+        history.push(now, stick.value, stick.drag_id.is_some());
+    }
+}
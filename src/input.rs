@@ -1,43 +1,153 @@
-use crate::{StickIdType, TouchStick, TouchStickEvent, TouchStickEventType, TouchStickType};
+use crate::{
+    gestures::{
+        handle_gesture_event, GestureInput, TouchStickGestures, TouchStickPinchEvent,
+        TouchStickRotateEvent,
+    },
+    sampling::TouchStickQuantization,
+    StickIdType, TouchStick, TouchStickDisabled, TouchStickEvent, TouchStickEventType,
+    TouchStickType,
+};
 use bevy::{
-    input::{mouse::MouseButtonInput, touch::TouchPhase, ButtonState},
+    input::{
+        mouse::MouseButtonInput,
+        touch::{ForceTouch, TouchPhase},
+        ButtonState,
+    },
     prelude::*,
     window::PrimaryWindow,
 };
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Drag ids of emulated drags have this bit set, so they don't collide with
+/// touch ids or the mouse
+pub(crate) const EMULATED_DRAG_ID: u64 = 1 << 63;
+
 #[derive(Event)]
 /// Actual device input passed too [`TouchStick`]
+///
+/// `force` is the normalized touch force, if the device reports it.
 pub(crate) enum DragEvent {
-    Start { id: u64, position: Vec2 },
-    Drag { id: u64, position: Vec2 },
-    End { id: u64 },
+    Start {
+        id: u64,
+        position: Vec2,
+        force: Option<f32>,
+    },
+    Drag {
+        id: u64,
+        position: Vec2,
+        force: Option<f32>,
+    },
+    End {
+        id: u64,
+    },
+}
+
+impl DragEvent {
+    /// Id of the drag the event belongs to
+    pub(crate) fn id(&self) -> u64 {
+        match *self {
+            DragEvent::Start { id, .. } | DragEvent::Drag { id, .. } | DragEvent::End { id } => id,
+        }
+    }
+
+    /// Whether the event was emulated for a single stick, e.g. by its keyboard emulation
+    pub(crate) fn is_emulated(&self) -> bool {
+        self.id() & EMULATED_DRAG_ID != 0
+    }
+}
+
+/// Drag id of drags emulated for the stick on `entity`
+pub(crate) fn emulated_drag_id(entity: Entity) -> u64 {
+    EMULATED_DRAG_ID | entity.index() as u64
 }
 
+/// Whether a drag with `id` may press the stick on `entity`
+///
+/// Emulated drags only press the stick they were emulated for, wherever they start.
+fn targets_stick(id: u64, entity: Entity) -> bool {
+    id & EMULATED_DRAG_ID == 0 || id == emulated_drag_id(entity)
+}
+
+#[allow(clippy::type_complexity)]
 pub(crate) fn update_sticks_from_drag_events<S: StickIdType>(
+    quantization: Res<TouchStickQuantization>,
     mut drag_events: EventReader<DragEvent>,
     mut stick_events: EventWriter<TouchStickEvent<S>>,
-    mut sticks: Query<&mut TouchStick<S>>,
+    mut pinch_events: EventWriter<TouchStickPinchEvent<S>>,
+    mut rotate_events: EventWriter<TouchStickRotateEvent<S>>,
+    mut sticks: Query<(
+        Entity,
+        &mut TouchStick<S>,
+        Has<TouchStickDisabled>,
+        Option<&mut TouchStickGestures>,
+    )>,
 ) {
     let input_events = drag_events.read().collect::<Vec<&DragEvent>>();
 
-    for mut stick in &mut sticks {
+    for (entity, mut stick, disabled, mut gestures) in &mut sticks {
+        if disabled {
+            if let Some(gestures) = gestures.as_mut() {
+                gestures.clear();
+            }
+            if stick.drag_id.is_some() {
+                release_stick(entity, &mut stick, &mut stick_events);
+            }
+            continue;
+        }
+
         for event in &input_events {
-            match event {
-                DragEvent::Start { id, position } => {
-                    if stick.interactable_zone.contains(*position) && stick.drag_id != Some(*id) {
-                        stick.drag_id = Some(*id);
-                        stick.drag_start = *position;
-                        stick.drag_position = *position;
-                        stick.value = Vec2::ZERO;
-                        stick_events.send(TouchStickEvent {
-                            id: stick.id.clone(),
-                            event: TouchStickEventType::Press,
-                            value: Vec2::ZERO,
-                        });
+            if let Some(gestures) = gestures.as_mut() {
+                match handle_gesture_event(
+                    gestures,
+                    entity,
+                    &stick,
+                    event,
+                    &mut pinch_events,
+                    &mut rotate_events,
+                ) {
+                    GestureInput::Ignored => {}
+                    GestureInput::Consumed => continue,
+                    GestureInput::Started => {
+                        release_stick(entity, &mut stick, &mut stick_events);
+                        continue;
                     }
                 }
-                DragEvent::Drag { id, position: pos } if Some(*id) == stick.drag_id => {
+            }
+
+            match event {
+                DragEvent::Start {
+                    id,
+                    position,
+                    force,
+                } if stick.interactable_zone.contains(*position)
+                    && stick.drag_id != Some(*id)
+                    && targets_stick(*id, entity) =>
+                {
+                    stick.drag_id = Some(*id);
+                    stick.drag_start = match stick.stick_type {
+                        TouchStickType::Fixed => *position,
+                        _ => stick.clamp_base(*position),
+                    };
+                    stick.drag_position = *position;
+                    stick.value = Vec2::ZERO;
+                    stick.pressure = *force;
+                    stick_events.send(TouchStickEvent {
+                        id: stick.id.clone(),
+                        entity,
+                        event: TouchStickEventType::Press,
+                        value: Vec2::ZERO,
+                        pressure: stick.pressure,
+                    });
+                }
+                DragEvent::Drag {
+                    id,
+                    position: pos,
+                    force,
+                } if Some(*id) == stick.drag_id => {
                     stick.drag_position = *pos;
+                    stick.pressure = *force;
                     let radius = stick.radius;
                     if stick.stick_type == TouchStickType::Dynamic {
                         stick.base_position = *pos;
@@ -47,23 +157,19 @@ pub(crate) fn update_sticks_from_drag_events<S: StickIdType>(
                             let excess_distance = distance_to_knob - radius;
                             stick.drag_start += to_knob.normalize() * excess_distance;
                         }
+                        stick.drag_start = stick.clamp_base(stick.drag_start);
+                        stick.base_position = stick.clamp_base(stick.base_position);
                     }
-                    let d = (stick.drag_position - stick.drag_start) / radius;
+                    let d = (stick.drag_position - stick.drag_start) * stick.sensitivity / radius;
                     let length = d.length();
                     // input events are y positive down, so we flip it
-                    stick.value = Vec2::new(d.x, -d.y) / length.max(1.);
+                    let value = Vec2::new(d.x, -d.y) / length.max(1.);
+                    // before the dead zone check, so events see the same
+                    // values as remote peers
+                    stick.value = quantization.quantize(value);
                 }
                 DragEvent::End { id } if Some(*id) == stick.drag_id => {
-                    stick.drag_id = None;
-                    stick.base_position = Vec2::ZERO;
-                    stick.drag_start = Vec2::ZERO;
-                    stick.drag_position = Vec2::ZERO;
-                    stick.value = Vec2::ZERO;
-                    stick_events.send(TouchStickEvent {
-                        id: stick.id.clone(),
-                        event: TouchStickEventType::Release,
-                        value: Vec2::ZERO,
-                    });
+                    release_stick(entity, &mut stick, &mut stick_events);
                 }
                 _ => {}
             }
@@ -75,34 +181,58 @@ pub(crate) fn update_sticks_from_drag_events<S: StickIdType>(
         {
             stick_events.send(TouchStickEvent {
                 id: stick.id.clone(),
+                entity,
                 event: TouchStickEventType::Drag,
                 value: stick.value,
+                pressure: stick.pressure,
             });
         }
     }
 }
 
+fn release_stick<S: StickIdType>(
+    entity: Entity,
+    stick: &mut TouchStick<S>,
+    stick_events: &mut EventWriter<TouchStickEvent<S>>,
+) {
+    stick.drag_id = None;
+    stick.base_position = Vec2::ZERO;
+    stick.drag_start = Vec2::ZERO;
+    stick.drag_position = Vec2::ZERO;
+    stick.value = Vec2::ZERO;
+    stick.pressure = None;
+    stick_events.send(TouchStickEvent {
+        id: stick.id.clone(),
+        entity,
+        event: TouchStickEventType::Release,
+        value: Vec2::ZERO,
+        pressure: None,
+    });
+}
+
 pub(crate) fn send_drag_events_from_touch(
     mut touch_events: EventReader<TouchInput>,
     mut send_values: EventWriter<DragEvent>,
 ) {
     let touches = touch_events
         .read()
-        .map(|e| (e.id, e.phase, e.position))
-        .collect::<Vec<(u64, TouchPhase, Vec2)>>();
+        .map(|e| (e.id, e.phase, e.position, e.force.map(normalized_force)))
+        .collect::<Vec<(u64, TouchPhase, Vec2, Option<f32>)>>();
 
-    for (id, phase, position) in &touches {
+    for (id, phase, position, force) in &touches {
         match phase {
             TouchPhase::Started => {
                 send_values.send(DragEvent::Start {
                     id: *id,
                     position: *position,
+                    force: *force,
                 });
             }
             TouchPhase::Moved => {
                 send_values.send(DragEvent::Drag {
                     id: *id,
                     position: *position,
+                    force: *force,
                 });
             }
             TouchPhase::Ended | TouchPhase::Canceled => {
@@ -112,34 +242,154 @@ pub(crate) fn send_drag_events_from_touch(
     }
 }
 
+/// Touch force in the range `0.0..=1.0`, where 1 is the maximum the device can report
+fn normalized_force(force: ForceTouch) -> f32 {
+    let force = match force {
+        ForceTouch::Calibrated {
+            force,
+            max_possible_force,
+            ..
+        } if max_possible_force > 0. => force / max_possible_force,
+        ForceTouch::Calibrated { force, .. } => force,
+        ForceTouch::Normalized(force) => force,
+    };
+    (force as f32).clamp(0., 1.)
+}
+
+/// What a mouse drag does when the cursor leaves the primary window
+///
+/// A release outside the window is only seen if the platform delivers it,
+/// which most desktop platforms do while a button is held. If it is lost,
+/// the next press of the button ends the old drag before starting a new one,
+/// and [`TouchStickRecovery`](crate::TouchStickRecovery) can end it earlier
+/// on focus loss or after a timeout.
+#[derive(Resource, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[reflect(Resource, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TouchStickMouseLeave {
+    /// Release the stick, it stays released until the button is pressed again
+    Release,
+    /// Keep the stick at its last value until the cursor comes back
+    #[default]
+    KeepLast,
+    /// Keep following the cursor, clamped to the window edge
+    ClampToEdge,
+}
+
 pub(crate) fn send_drag_events_from_mouse(
-    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    leave_behavior: Res<TouchStickMouseLeave>,
     mut mouse_events: EventReader<MouseButtonInput>,
+    mut cursor_events: EventReader<CursorMoved>,
     mut drag_events: EventWriter<DragEvent>,
-    primary_window: Query<&Window, With<PrimaryWindow>>,
+    primary_window: Query<(Entity, &Window), With<PrimaryWindow>>,
+    mut dragging: Local<bool>,
+    mut last_cursor_position: Local<Option<Vec2>>,
 ) {
-    let primary_window = primary_window.single();
+    let (window_entity, primary_window) = primary_window.single();
     let position = primary_window.cursor_position();
 
+    // some platforms keep reporting the cursor outside the window while a
+    // button is held
+    for cursor_event in cursor_events.read() {
+        if cursor_event.window == window_entity {
+            *last_cursor_position = Some(cursor_event.position);
+        }
+    }
+
     for mouse_event in mouse_events.read() {
         if mouse_event.button == MouseButton::Left && mouse_event.state == ButtonState::Released {
             drag_events.send(DragEvent::End { id: 0 });
+            *dragging = false;
         }
 
         if mouse_event.button == MouseButton::Left && mouse_event.state == ButtonState::Pressed {
+            // a press while dragging means the release was lost, e.g. outside the window
+            if *dragging {
+                drag_events.send(DragEvent::End { id: 0 });
+            }
             drag_events.send(DragEvent::Start {
                 id: 0,
                 position: position.unwrap_or_default(),
+                force: None,
             });
+            *dragging = true;
         }
     }
 
-    if mouse_buttons.pressed(MouseButton::Left) {
-        // if the mouse is outside the window, we'll still get pressed state,
-        // but we won't get the position. So in that case, we stop sending drag
-        // events.
-        if let Some(position) = position {
-            drag_events.send(DragEvent::Drag { id: 0, position });
+    if !*dragging {
+        return;
+    }
+
+    let position = match (position, *leave_behavior) {
+        (Some(position), _) => position,
+        (None, TouchStickMouseLeave::Release) => {
+            drag_events.send(DragEvent::End { id: 0 });
+            *dragging = false;
+            return;
         }
+        (None, TouchStickMouseLeave::KeepLast) => return,
+        (None, TouchStickMouseLeave::ClampToEdge) => {
+            let Some(last_position) = *last_cursor_position else {
+                return;
+            };
+            let window_size = Vec2::new(primary_window.width(), primary_window.height());
+            last_position.clamp(Vec2::ZERO, window_size)
+        }
+    };
+    drag_events.send(DragEvent::Drag {
+        id: 0,
+        position,
+        force: None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::InputPlugin;
+
+    use super::*;
+    use crate::TouchStickPlugin;
+
+    fn press_at(app: &mut App, window: Entity, position: Vec2) {
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .set_cursor_position(Some(position));
+        app.world.send_event(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+            window,
+        });
+        app.update();
+    }
+
+    #[test]
+    fn press_while_dragging_restarts_drag() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            InputPlugin,
+            TouchStickPlugin::<()>::default(),
+        ));
+        let window = app.world.spawn((Window::default(), PrimaryWindow)).id();
+        let stick = app.world.spawn(TouchStick::<()>::default()).id();
+
+        press_at(&mut app, window, Vec2::new(100., 100.));
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .set_cursor_position(Some(Vec2::new(130., 100.)));
+        app.update();
+        assert_ne!(
+            app.world.get::<TouchStick>(stick).unwrap().value,
+            Vec2::ZERO
+        );
+
+        // the release of the first drag was never delivered
+        press_at(&mut app, window, Vec2::new(200., 200.));
+        let touch_stick = app.world.get::<TouchStick>(stick).unwrap();
+        assert_eq!(touch_stick.drag_id, Some(0));
+        assert_eq!(touch_stick.drag_start, Vec2::new(200., 200.));
+        assert_eq!(touch_stick.value, Vec2::ZERO);
     }
 }
@@ -0,0 +1,169 @@
+use bevy::{prelude::*, utils::HashSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input::{emulated_drag_id, DragEvent},
+    StickIdType, TouchStick, TouchStickDisabled,
+};
+
+/// Drives a [`TouchStick`] with keys, e.g. for testing on desktop
+///
+/// Add to a [`TouchStick`]. Held keys are turned into drag events at the
+/// center of the stick zone, so the stick, its visuals, [`TouchStickEvent`](crate::TouchStickEvent)s
+/// and gamepad axes behave exactly as if the stick was dragged. The mouse
+/// already drives sticks the same way by dragging with the left button.
+///
+/// The bound keys don't count as keyboard usage for the
+/// [`InputModalityPlugin`](crate::InputModalityPlugin), so emulated sticks
+/// aren't hidden by the keys driving them.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickKeyboardEmulation {
+    /// Key pushing the stick up
+    pub up: KeyCode,
+    /// Key pushing the stick down
+    pub down: KeyCode,
+    /// Key pushing the stick left
+    pub left: KeyCode,
+    /// Key pushing the stick right
+    pub right: KeyCode,
+}
+
+impl TouchStickKeyboardEmulation {
+    /// Maps W, A, S and D
+    pub fn wasd() -> Self {
+        Self {
+            up: KeyCode::KeyW,
+            down: KeyCode::KeyS,
+            left: KeyCode::KeyA,
+            right: KeyCode::KeyD,
+        }
+    }
+
+    /// Maps the arrow keys
+    pub fn arrows() -> Self {
+        Self {
+            up: KeyCode::ArrowUp,
+            down: KeyCode::ArrowDown,
+            left: KeyCode::ArrowLeft,
+            right: KeyCode::ArrowRight,
+        }
+    }
+
+    /// Whether `key` drives the stick
+    pub(crate) fn is_bound(&self, key: KeyCode) -> bool {
+        [self.up, self.down, self.left, self.right].contains(&key)
+    }
+
+    /// Direction of the held keys, with y up
+    fn direction(&self, keys: &ButtonInput<KeyCode>) -> Vec2 {
+        let axis = |negative, positive| {
+            keys.pressed(positive) as i8 as f32 - keys.pressed(negative) as i8 as f32
+        };
+        Vec2::new(axis(self.left, self.right), axis(self.down, self.up))
+    }
+}
+
+impl Default for TouchStickKeyboardEmulation {
+    fn default() -> Self {
+        Self::wasd()
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn send_drag_events_from_keyboard<S: StickIdType>(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    sticks: Query<
+        (Entity, &TouchStick<S>, &TouchStickKeyboardEmulation),
+        Without<TouchStickDisabled>,
+    >,
+    mut drag_events: EventWriter<DragEvent>,
+    mut active: Local<HashSet<Entity>>,
+) {
+    let mut still_active = HashSet::new();
+
+    if let Some(keys) = keys {
+        for (entity, stick, emulation) in &sticks {
+            let direction = emulation.direction(&keys).normalize_or_zero();
+            if direction == Vec2::ZERO {
+                continue;
+            }
+
+            let id = emulated_drag_id(entity);
+            let center = stick.interactable_zone.center();
+            if !active.contains(&entity) {
+                drag_events.send(DragEvent::Start {
+                    id,
+                    position: center,
+                    force: None,
+                });
+            }
+            // input events are y positive down, so we flip it
+            let offset = Vec2::new(direction.x, -direction.y) * stick.radius
+                / stick.sensitivity.max(f32::EPSILON);
+            drag_events.send(DragEvent::Drag {
+                id,
+                position: center + offset,
+                force: None,
+            });
+            still_active.insert(entity);
+        }
+    }
+
+    for entity in active.difference(&still_active) {
+        drag_events.send(DragEvent::End {
+            id: emulated_drag_id(*entity),
+        });
+    }
+    *active = still_active;
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{input::InputPlugin, window::PrimaryWindow};
+
+    use super::*;
+    use crate::TouchStickPlugin;
+
+    #[test]
+    fn emulation_only_drives_its_stick() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            InputPlugin,
+            TouchStickPlugin::<()>::default(),
+        ));
+        app.world.spawn((Window::default(), PrimaryWindow));
+        // both zones contain the point the emulated drag starts at
+        let emulated = app
+            .world
+            .spawn((
+                TouchStick::<()>::default(),
+                TouchStickKeyboardEmulation::wasd(),
+            ))
+            .id();
+        let other = app.world.spawn(TouchStick::<()>::default()).id();
+
+        app.world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyW);
+        app.update();
+
+        let stick = app.world.get::<TouchStick>(emulated).unwrap();
+        assert!(stick.drag_id.is_some());
+        assert_eq!(stick.value, Vec2::Y);
+        let stick = app.world.get::<TouchStick>(other).unwrap();
+        assert_eq!(stick.drag_id, None);
+        assert_eq!(stick.value, Vec2::ZERO);
+
+        app.world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .release(KeyCode::KeyW);
+        app.update();
+
+        assert_eq!(app.world.get::<TouchStick>(emulated).unwrap().drag_id, None);
+    }
+}
@@ -0,0 +1,587 @@
+use bevy::{prelude::*, ui::UiSystem, window::PrimaryWindow};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "gamepad_mapping")]
+use crate::TouchStickGamepadMapping;
+use crate::{
+    input::DragEvent,
+    mirror::{mirror_anchor, mirror_stick_nodes, mirror_style, Mirrored},
+    TouchSafeAreaAnchor, TouchStick, TouchStickDisabledBy, TouchStickInteractionArea,
+    TouchStickSystem, TouchStickType, TouchStickUiStyle,
+};
+
+/// Positions and sizes of stick nodes, e.g. as arranged in the [`TouchLayoutEditor`]
+///
+/// Sticks are matched by their [`Name`]. While this resource exists, it is
+/// applied to the [`Style`] of all matching stick nodes, including ones
+/// spawned later, so a saved layout can be inserted at startup.
+///
+/// A [`TouchLayout`] is also an asset, which can describe complete sticks
+/// and be spawned with [`TouchLayoutRoot`](crate::TouchLayoutRoot).
+#[derive(Asset, Resource, Reflect, Clone, Debug, Default, PartialEq)]
+#[reflect(Resource, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchLayout {
+    /// Layout of each stick
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sticks: Vec<TouchLayoutStick>,
+}
+
+impl TouchLayout {
+    /// Returns the layout of the stick with the given name
+    pub fn get(&self, name: &str) -> Option<&TouchLayoutStick> {
+        self.sticks.iter().find(|stick| stick.name == name)
+    }
+
+    /// Inserts or replaces the layout of a stick
+    pub fn insert(&mut self, stick: TouchLayoutStick) {
+        match self.sticks.iter_mut().find(|s| s.name == stick.name) {
+            Some(existing) => *existing = stick,
+            None => self.sticks.push(stick),
+        }
+    }
+}
+
+/// Layout of a single stick node in a [`TouchLayout`]
+#[derive(Reflect, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchLayoutStick {
+    /// [`Name`] of the stick node
+    pub name: String,
+    /// Offset of the top left corner from the parent node, in logical pixels
+    pub position: Vec2,
+    /// Size of the node, in logical pixels
+    pub size: Vec2,
+    /// Stick type when spawned by a [`TouchLayoutRoot`](crate::TouchLayoutRoot)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stick_type: TouchStickType,
+    /// Stick radius when spawned by a [`TouchLayoutRoot`](crate::TouchLayoutRoot)
+    #[cfg_attr(feature = "serde", serde(default = "default_radius"))]
+    pub radius: f32,
+    /// Stick dead zone when spawned by a [`TouchLayoutRoot`](crate::TouchLayoutRoot)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dead_zone: f32,
+    /// Gamepad mapping when spawned by a [`TouchLayoutRoot`](crate::TouchLayoutRoot)
+    #[cfg(feature = "gamepad_mapping")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gamepad_mapping: Option<TouchStickGamepadMapping>,
+    /// Knob and outline images when spawned by a [`TouchLayoutRoot`](crate::TouchLayoutRoot)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub visuals: Option<TouchLayoutVisuals>,
+}
+
+impl Default for TouchLayoutStick {
+    fn default() -> Self {
+        Self {
+            name: default(),
+            position: default(),
+            size: default(),
+            stick_type: default(),
+            radius: default_radius(),
+            dead_zone: 0.,
+            #[cfg(feature = "gamepad_mapping")]
+            gamepad_mapping: None,
+            visuals: None,
+        }
+    }
+}
+
+fn default_radius() -> f32 {
+    TouchStick::<()>::default().radius
+}
+
+/// Images of a stick spawned from a [`TouchLayout`]
+#[derive(Reflect, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchLayoutVisuals {
+    /// Asset path of the [`TouchStickUiKnob`](crate::TouchStickUiKnob) image
+    pub knob_image: String,
+    /// Size of the knob, in logical pixels
+    pub knob_size: Vec2,
+    /// Asset path of the [`TouchStickUiOutline`](crate::TouchStickUiOutline) image
+    pub outline_image: String,
+    /// Size of the outline, in logical pixels
+    pub outline_size: Vec2,
+    /// Colors of the knob and outline
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub style: Option<TouchStickUiStyle>,
+}
+
+impl TouchLayoutStick {
+    /// Positions the node, from the right edge of the parent if `mirrored`
+    pub(crate) fn apply(&self, style: &mut Style, mirrored: bool) {
+        style.position_type = PositionType::Absolute;
+        style.left = Val::Px(self.position.x);
+        style.top = Val::Px(self.position.y);
+        style.right = Val::Auto;
+        style.bottom = Val::Auto;
+        if mirrored {
+            std::mem::swap(&mut style.left, &mut style.right);
+        }
+        style.width = Val::Px(self.size.x);
+        style.height = Val::Px(self.size.y);
+    }
+}
+
+/// Lets players move and resize stick nodes by dragging them
+///
+/// While enabled, sticks don't produce input. Dragging a stick moves it, and
+/// dragging its bottom right corner resizes it. The result is stored in the
+/// [`TouchLayout`] resource, which is inserted if missing. Only sticks with a
+/// [`Name`] can be edited.
+///
+/// Positions are always stored unmirrored, so a layout edited while the
+/// [`TouchStickLayoutMirror`](crate::TouchStickLayoutMirror) is enabled is
+/// mirrored like any other layout.
+#[derive(Resource, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Resource, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchLayoutEditor {
+    /// Whether edit mode is active
+    pub enabled: bool,
+    /// Snap positions and sizes to multiples of this, `0` disables snapping
+    pub grid_size: f32,
+    /// Size of the bottom right corner that resizes instead of moves
+    pub resize_handle_size: f32,
+    /// Smallest size a stick node can be resized to
+    pub min_size: f32,
+}
+
+impl Default for TouchLayoutEditor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            grid_size: 10.,
+            resize_handle_size: 40.,
+            min_size: 50.,
+        }
+    }
+}
+
+/// Resets all stick nodes to the [`Style`] they were spawned with and clears
+/// the [`TouchLayout`]
+#[derive(Event, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ResetTouchLayout;
+
+pub(crate) struct TouchLayoutEditorPlugin;
+
+impl Plugin for TouchLayoutEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TouchLayout>()
+            .register_type::<TouchLayoutEditor>()
+            .init_resource::<TouchLayoutEditor>()
+            .add_event::<ResetTouchLayout>()
+            .add_systems(
+                PreUpdate,
+                (
+                    disable_sticks_while_editing.before(TouchStickSystem::DragEvents),
+                    edit_layout.after(TouchStickSystem::DragEvents),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                (reset_touch_layout, apply_touch_layout)
+                    .chain()
+                    // positions are unmirrored, new nodes are mirrored afterwards
+                    .before(mirror_stick_nodes)
+                    .before(UiSystem::Layout),
+            );
+    }
+}
+
+/// The unmirrored [`Style`] and anchor of a stick node before it was changed
+/// by a [`TouchLayout`]
+#[derive(Component)]
+struct DefaultStyle(Style, Option<TouchSafeAreaAnchor>);
+
+impl DefaultStyle {
+    fn new(style: &Style, anchor: Option<&Mut<TouchSafeAreaAnchor>>, mirrored: bool) -> Self {
+        let mut style = style.clone();
+        let mut anchor = anchor.map(|anchor| **anchor);
+        if mirrored {
+            mirror_style(&mut style);
+            anchor = anchor.map(mirror_anchor);
+        }
+        Self(style, anchor)
+    }
+}
+
+/// Marker for sticks disabled by the [`TouchLayoutEditor`]
+#[derive(Component)]
+struct DisabledByEditor;
+
+#[allow(clippy::type_complexity)]
+fn disable_sticks_while_editing(
+    mut commands: Commands,
+    editor: Res<TouchLayoutEditor>,
+    enabled_sticks: Query<Entity, (With<TouchStickInteractionArea>, Without<DisabledByEditor>)>,
+    disabled_by_editor: Query<Entity, With<DisabledByEditor>>,
+) {
+    if editor.enabled {
+        for entity in &enabled_sticks {
+            commands
+                .entity(entity)
+                .insert(DisabledByEditor)
+                .add(TouchStickDisabledBy::disable(
+                    TouchStickDisabledBy::LAYOUT_EDITOR,
+                ));
+        }
+    } else {
+        for entity in &disabled_by_editor {
+            commands
+                .entity(entity)
+                .remove::<DisabledByEditor>()
+                .add(TouchStickDisabledBy::enable(
+                    TouchStickDisabledBy::LAYOUT_EDITOR,
+                ));
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum EditMode {
+    Move,
+    Resize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct EditDrag {
+    drag_id: u64,
+    entity: Entity,
+    mode: EditMode,
+    mirrored: bool,
+    pointer_start: Vec2,
+    /// Unmirrored position, see [`TouchLayoutStick::position`]
+    position_start: Vec2,
+    size_start: Vec2,
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn edit_layout(
+    mut commands: Commands,
+    editor: Res<TouchLayoutEditor>,
+    mut layout: Option<ResMut<TouchLayout>>,
+    mut drag_events: EventReader<DragEvent>,
+    mut sticks: Query<
+        (
+            Entity,
+            &Name,
+            &Node,
+            &GlobalTransform,
+            &mut Style,
+            Option<&Parent>,
+            Option<&mut TouchSafeAreaAnchor>,
+            Option<&DefaultStyle>,
+            Has<Mirrored>,
+        ),
+        With<TouchStickInteractionArea>,
+    >,
+    parents: Query<(&Node, &GlobalTransform)>,
+    primary_window: Query<&Window, With<PrimaryWindow>>,
+    mut current: Local<Option<EditDrag>>,
+) {
+    if !editor.enabled {
+        drag_events.clear();
+        *current = None;
+        return;
+    }
+
+    for event in drag_events.read() {
+        // emulated drags don't point anywhere on screen
+        if event.is_emulated() {
+            continue;
+        }
+        match *event {
+            DragEvent::Start { id, position, .. } if current.is_none() => {
+                let hit = sticks.iter().find_map(
+                    |(entity, _, node, transform, _, parent, _, _, mirrored)| {
+                        let center = transform.translation().truncate();
+                        let rect = Rect::from_center_size(center, node.size());
+                        rect.contains(position)
+                            .then_some((entity, rect, parent, mirrored))
+                    },
+                );
+                let Some((entity, rect, parent, mirrored)) = hit else {
+                    continue;
+                };
+                let parent_rect = parent
+                    .and_then(|parent| parents.get(parent.get()).ok())
+                    .map(|(node, transform)| {
+                        Rect::from_center_size(transform.translation().truncate(), node.size())
+                    })
+                    .or_else(|| {
+                        let window = primary_window.get_single().ok()?;
+                        Some(Rect::new(0., 0., window.width(), window.height()))
+                    })
+                    .unwrap_or_default();
+                let mut position_start = rect.min - parent_rect.min;
+                if mirrored {
+                    position_start.x = parent_rect.max.x - rect.max.x;
+                }
+                let in_handle = (rect.max - position).max_element() <= editor.resize_handle_size;
+                *current = Some(EditDrag {
+                    drag_id: id,
+                    entity,
+                    mode: if in_handle {
+                        EditMode::Resize
+                    } else {
+                        EditMode::Move
+                    },
+                    mirrored,
+                    pointer_start: position,
+                    position_start,
+                    size_start: rect.size(),
+                });
+            }
+            DragEvent::Drag { id, position, .. } => {
+                let Some(drag) = current.filter(|drag| drag.drag_id == id) else {
+                    continue;
+                };
+                let Ok((entity, name, _, _, mut style, _, anchor, default_style, mirrored)) =
+                    sticks.get_mut(drag.entity)
+                else {
+                    *current = None;
+                    continue;
+                };
+                if mirrored != drag.mirrored {
+                    *current = None;
+                    continue;
+                }
+
+                let delta = position - drag.pointer_start;
+                let (new_position, new_size) = match drag.mode {
+                    EditMode::Move => {
+                        let delta = if mirrored {
+                            Vec2::new(-delta.x, delta.y)
+                        } else {
+                            delta
+                        };
+                        (
+                            snap(drag.position_start + delta, editor.grid_size),
+                            drag.size_start,
+                        )
+                    }
+                    EditMode::Resize => {
+                        let new_size = snap(drag.size_start + delta, editor.grid_size)
+                            .max(Vec2::splat(editor.min_size));
+                        let mut new_position = drag.position_start;
+                        // keep the left edge on screen in place
+                        if mirrored {
+                            new_position.x -= new_size.x - drag.size_start.x;
+                        }
+                        (new_position, new_size)
+                    }
+                };
+
+                if default_style.is_none() {
+                    commands.entity(entity).insert(DefaultStyle::new(
+                        &style,
+                        anchor.as_ref(),
+                        mirrored,
+                    ));
+                }
+                if let Some(mut anchor) = anchor {
+                    *anchor = TouchSafeAreaAnchor::None;
+                }
+
+                let mut stick_layout = layout
+                    .as_ref()
+                    .and_then(|layout| layout.get(name.as_str()))
+                    .cloned()
+                    .unwrap_or_else(|| TouchLayoutStick {
+                        name: name.to_string(),
+                        ..default()
+                    });
+                stick_layout.position = new_position;
+                stick_layout.size = new_size;
+                stick_layout.apply(&mut style, mirrored);
+                match layout.as_mut() {
+                    Some(layout) => layout.insert(stick_layout),
+                    None => {
+                        let mut new_layout = TouchLayout::default();
+                        new_layout.insert(stick_layout);
+                        commands.insert_resource(new_layout);
+                    }
+                }
+            }
+            DragEvent::End { id } if current.is_some_and(|drag| drag.drag_id == id) => {
+                *current = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn snap(value: Vec2, grid_size: f32) -> Vec2 {
+    if grid_size > 0. {
+        (value / grid_size).round() * grid_size
+    } else {
+        value
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_touch_layout(
+    mut commands: Commands,
+    layout: Option<Res<TouchLayout>>,
+    mut sticks: Query<
+        (
+            Entity,
+            Ref<Name>,
+            &mut Style,
+            Option<&mut TouchSafeAreaAnchor>,
+            Option<&DefaultStyle>,
+            Has<Mirrored>,
+        ),
+        With<TouchStickInteractionArea>,
+    >,
+) {
+    let Some(layout) = layout else {
+        return;
+    };
+
+    for (entity, name, mut style, anchor, default_style, mirrored) in &mut sticks {
+        if !layout.is_changed() && !name.is_added() {
+            continue;
+        }
+        let Some(stick_layout) = layout.get(name.as_str()) else {
+            continue;
+        };
+
+        if default_style.is_none() {
+            commands
+                .entity(entity)
+                .insert(DefaultStyle::new(&style, anchor.as_ref(), mirrored));
+        }
+        if let Some(mut anchor) = anchor {
+            if *anchor != TouchSafeAreaAnchor::None {
+                *anchor = TouchSafeAreaAnchor::None;
+            }
+        }
+        stick_layout.apply(&mut style, mirrored);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn reset_touch_layout(
+    mut commands: Commands,
+    mut reset_events: EventReader<ResetTouchLayout>,
+    mut sticks: Query<(
+        Entity,
+        &mut Style,
+        Option<&mut TouchSafeAreaAnchor>,
+        &DefaultStyle,
+        Has<Mirrored>,
+    )>,
+) {
+    if reset_events.read().count() == 0 {
+        return;
+    }
+
+    for (entity, mut style, anchor, DefaultStyle(default_style, default_anchor), mirrored) in
+        &mut sticks
+    {
+        *style = default_style.clone();
+        if mirrored {
+            mirror_style(&mut style);
+        }
+        if let (Some(mut anchor), Some(default_anchor)) = (anchor, default_anchor) {
+            *anchor = if mirrored {
+                mirror_anchor(*default_anchor)
+            } else {
+                *default_anchor
+            };
+        }
+        commands.entity(entity).remove::<DefaultStyle>();
+    }
+    commands.remove_resource::<TouchLayout>();
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::InputPlugin;
+
+    use super::*;
+    use crate::{TouchStickLayoutMirror, TouchStickPlugin};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            InputPlugin,
+            TouchStickPlugin::<()>::default(),
+        ));
+        app.world.spawn((Window::default(), PrimaryWindow));
+        app
+    }
+
+    fn spawn_stick(app: &mut App) -> Entity {
+        app.world
+            .spawn((
+                Name::new("left"),
+                TouchStickInteractionArea,
+                Style {
+                    left: Val::Px(10.),
+                    ..default()
+                },
+            ))
+            .id()
+    }
+
+    fn layout(position: Vec2) -> TouchLayout {
+        TouchLayout {
+            sticks: vec![TouchLayoutStick {
+                name: "left".into(),
+                position,
+                size: Vec2::splat(100.),
+                ..default()
+            }],
+        }
+    }
+
+    fn horizontal_position(app: &App, entity: Entity) -> (Val, Val) {
+        let style = app.world.get::<Style>(entity).unwrap();
+        (style.left, style.right)
+    }
+
+    #[test]
+    fn applies_layout_while_mirrored() {
+        let mut app = app();
+        let stick = spawn_stick(&mut app);
+        app.world.resource_mut::<TouchStickLayoutMirror>().enabled = true;
+        app.update();
+        assert_eq!(horizontal_position(&app, stick), (Val::Auto, Val::Px(10.)));
+
+        app.insert_resource(layout(Vec2::new(20., 30.)));
+        app.update();
+        app.update();
+        assert_eq!(horizontal_position(&app, stick), (Val::Auto, Val::Px(20.)));
+        assert_eq!(app.world.get::<Style>(stick).unwrap().top, Val::Px(30.));
+
+        app.world.resource_mut::<TouchStickLayoutMirror>().enabled = false;
+        app.update();
+        assert_eq!(horizontal_position(&app, stick), (Val::Px(20.), Val::Auto));
+
+        app.world.send_event(ResetTouchLayout);
+        app.world.resource_mut::<TouchStickLayoutMirror>().enabled = true;
+        app.update();
+        assert_eq!(horizontal_position(&app, stick), (Val::Auto, Val::Px(10.)));
+    }
+
+    #[test]
+    fn mirrors_layout_applied_at_startup() {
+        let mut app = app();
+        app.insert_resource(TouchStickLayoutMirror {
+            enabled: true,
+            ..default()
+        });
+        app.insert_resource(layout(Vec2::new(20., 30.)));
+        let stick = spawn_stick(&mut app);
+        app.update();
+        app.update();
+        assert_eq!(horizontal_position(&app, stick), (Val::Auto, Val::Px(20.)));
+    }
+}
@@ -0,0 +1,309 @@
+use std::marker::PhantomData;
+
+use bevy::{prelude::*, utils::HashSet};
+
+#[cfg(feature = "gamepad_mapping")]
+use crate::TouchStickGamepadMapping;
+use crate::{
+    layout::TouchLayoutVisuals, mirror::Mirrored, StickIdType, TouchLayout, TouchLayoutStick,
+    TouchStick, TouchStickUiBundle, TouchStickUiKnob, TouchStickUiOutline, TouchStickUiStyle,
+};
+
+/// Spawns the sticks of a [`TouchLayout`] asset as children of this node
+///
+/// Sticks are positioned absolutely, so this should usually be a node
+/// covering the window. When the asset is modified, e.g. by hot reloading a
+/// `.touch_layout.ron` file, existing sticks are updated in place, new sticks
+/// are spawned and removed sticks are despawned.
+///
+/// The knob and outline children of spawned sticks are managed by the layout
+/// and replaced on every change.
+#[derive(Component, Clone, Debug)]
+pub struct TouchLayoutRoot<S: StickIdType = ()> {
+    /// The layout to spawn
+    pub layout: Handle<TouchLayout>,
+    /// Ids of the spawned [`TouchStick`]s by [`TouchLayoutStick::name`],
+    /// sticks without an entry use the default id
+    pub ids: Vec<(String, S)>,
+}
+
+impl<S: StickIdType> TouchLayoutRoot<S> {
+    /// Creates a root spawning the given layout
+    pub fn new(layout: Handle<TouchLayout>) -> Self {
+        Self {
+            layout,
+            ids: default(),
+        }
+    }
+
+    /// Sets the id of the stick with the given name
+    pub fn with_id(mut self, name: impl Into<String>, id: S) -> Self {
+        self.ids.push((name.into(), id));
+        self
+    }
+
+    fn id(&self, name: &str) -> S {
+        self.ids
+            .iter()
+            .find(|(stick_name, _)| stick_name == name)
+            .map(|(_, id)| id.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Marker for sticks spawned by a [`TouchLayoutRoot`]
+///
+/// Marked changed whenever the layout overwrites the stick, so preferences can
+/// be applied again.
+#[derive(Component)]
+pub(crate) struct TouchLayoutSpawned;
+
+pub(crate) struct TouchLayoutAssetPlugin<S: StickIdType> {
+    _marker: PhantomData<S>,
+}
+
+impl<S: StickIdType> Default for TouchLayoutAssetPlugin<S> {
+    fn default() -> Self {
+        Self { _marker: default() }
+    }
+}
+
+impl<S: StickIdType> Plugin for TouchLayoutAssetPlugin<S> {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<AssetServer>() {
+            return;
+        }
+        if !app.world.contains_resource::<Assets<TouchLayout>>() {
+            app.init_asset::<TouchLayout>();
+            #[cfg(feature = "serde")]
+            app.init_asset_loader::<loader::TouchLayoutLoader>();
+        }
+        app.add_systems(Update, spawn_touch_layouts::<S>);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn spawn_touch_layouts<S: StickIdType>(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    layouts: Res<Assets<TouchLayout>>,
+    mut asset_events: EventReader<AssetEvent<TouchLayout>>,
+    roots: Query<(Entity, Ref<TouchLayoutRoot<S>>, Option<&Children>)>,
+    mut spawned: Query<(
+        &Name,
+        &mut TouchStick<S>,
+        &mut Style,
+        &mut TouchLayoutSpawned,
+        Has<Mirrored>,
+    )>,
+) {
+    let changed: HashSet<AssetId<TouchLayout>> = asset_events
+        .read()
+        .filter_map(|event| match *event {
+            AssetEvent::Added { id }
+            | AssetEvent::Modified { id }
+            | AssetEvent::LoadedWithDependencies { id } => Some(id),
+            _ => None,
+        })
+        .collect();
+
+    for (root_entity, root, children) in &roots {
+        if !root.is_changed() && !changed.contains(&root.layout.id()) {
+            continue;
+        }
+        let Some(layout) = layouts.get(&root.layout) else {
+            continue;
+        };
+
+        let mut existing = Vec::new();
+        for &child in children.into_iter().flatten() {
+            let Ok((name, ..)) = spawned.get(child) else {
+                continue;
+            };
+            if layout.get(name.as_str()).is_some() {
+                existing.push((name.to_string(), child));
+            } else {
+                commands.entity(child).despawn_recursive();
+            }
+        }
+
+        for stick_layout in &layout.sticks {
+            let id = root.id(&stick_layout.name);
+            let entity = match existing.iter().find(|(name, _)| *name == stick_layout.name) {
+                Some(&(_, entity)) => {
+                    let Ok((_, mut stick, mut style, mut marker, mirrored)) =
+                        spawned.get_mut(entity)
+                    else {
+                        continue;
+                    };
+                    marker.set_changed();
+                    stick.id = id;
+                    stick.stick_type = stick_layout.stick_type;
+                    stick.radius = stick_layout.radius;
+                    stick.dead_zone = stick_layout.dead_zone;
+                    stick_layout.apply(&mut style, mirrored);
+                    commands.entity(entity).despawn_descendants();
+                    entity
+                }
+                None => {
+                    // mirrored later like any other new stick node
+                    let mut style = Style::default();
+                    stick_layout.apply(&mut style, false);
+                    let entity = commands
+                        .spawn((
+                            Name::new(stick_layout.name.clone()),
+                            TouchLayoutSpawned,
+                            TouchStickUiBundle {
+                                stick: TouchStick {
+                                    id,
+                                    stick_type: stick_layout.stick_type,
+                                    radius: stick_layout.radius,
+                                    dead_zone: stick_layout.dead_zone,
+                                    ..default()
+                                },
+                                style,
+                                ..default()
+                            },
+                        ))
+                        .id();
+                    commands.entity(root_entity).add_child(entity);
+                    entity
+                }
+            };
+
+            update_stick_components(&mut commands, entity, stick_layout);
+            if let Some(visuals) = &stick_layout.visuals {
+                spawn_visuals(&mut commands, &asset_server, entity, visuals);
+            }
+        }
+    }
+}
+
+fn update_stick_components(commands: &mut Commands, entity: Entity, stick: &TouchLayoutStick) {
+    let mut entity = commands.entity(entity);
+
+    #[cfg(feature = "gamepad_mapping")]
+    match stick.gamepad_mapping {
+        Some(mapping) => {
+            entity.insert(mapping);
+        }
+        None => {
+            entity.remove::<TouchStickGamepadMapping>();
+        }
+    }
+
+    match stick
+        .visuals
+        .as_ref()
+        .and_then(|visuals| visuals.style.clone())
+    {
+        Some(style) => {
+            entity.insert(style);
+        }
+        None => {
+            entity.remove::<TouchStickUiStyle>();
+        }
+    }
+}
+
+fn spawn_visuals(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    entity: Entity,
+    visuals: &TouchLayoutVisuals,
+) {
+    commands.entity(entity).with_children(|parent| {
+        parent.spawn((
+            TouchStickUiKnob,
+            ImageBundle {
+                image: asset_server.load(visuals.knob_image.clone()).into(),
+                style: Style {
+                    width: Val::Px(visuals.knob_size.x),
+                    height: Val::Px(visuals.knob_size.y),
+                    ..default()
+                },
+                ..default()
+            },
+        ));
+        parent.spawn((
+            TouchStickUiOutline,
+            ImageBundle {
+                image: asset_server.load(visuals.outline_image.clone()).into(),
+                style: Style {
+                    width: Val::Px(visuals.outline_size.x),
+                    height: Val::Px(visuals.outline_size.y),
+                    ..default()
+                },
+                ..default()
+            },
+        ));
+    });
+}
+
+#[cfg(feature = "serde")]
+mod loader {
+    use std::fmt;
+
+    use bevy::{
+        asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+        utils::BoxedFuture,
+    };
+
+    use crate::TouchLayout;
+
+    /// Loads [`TouchLayout`]s from `.touch_layout.ron` files
+    #[derive(Default)]
+    pub(crate) struct TouchLayoutLoader;
+
+    #[derive(Debug)]
+    pub(crate) enum TouchLayoutLoaderError {
+        Io(std::io::Error),
+        Ron(ron::error::SpannedError),
+    }
+
+    impl fmt::Display for TouchLayoutLoaderError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Io(error) => write!(f, "could not read touch layout: {error}"),
+                Self::Ron(error) => write!(f, "could not parse touch layout: {error}"),
+            }
+        }
+    }
+
+    impl std::error::Error for TouchLayoutLoaderError {}
+
+    impl From<std::io::Error> for TouchLayoutLoaderError {
+        fn from(error: std::io::Error) -> Self {
+            Self::Io(error)
+        }
+    }
+
+    impl From<ron::error::SpannedError> for TouchLayoutLoaderError {
+        fn from(error: ron::error::SpannedError) -> Self {
+            Self::Ron(error)
+        }
+    }
+
+    impl AssetLoader for TouchLayoutLoader {
+        type Asset = TouchLayout;
+        type Settings = ();
+        type Error = TouchLayoutLoaderError;
+
+        fn load<'a>(
+            &'a self,
+            reader: &'a mut Reader,
+            _settings: &'a (),
+            _load_context: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<TouchLayout, TouchLayoutLoaderError>> {
+            Box::pin(async move {
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes).await?;
+                Ok(ron::de::from_bytes(&bytes)?)
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["touch_layout.ron"]
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::marker::PhantomData;
+
+use bevy::{prelude::*, utils::HashMap};
+use leafwing_input_manager::{
+    axislike::DualAxisData, buttonlike::ButtonState, plugin::InputManagerSystem, prelude::*,
+    timing::Timing,
+};
+
+use crate::{StickIdType, TouchStick, TouchStickSystem};
+
+/// Plugin that feeds [`TouchStick`]s directly into `leafwing-input-manager` actions
+///
+/// Unlike [`TouchStickGamepadMapping`](crate::TouchStickGamepadMapping), this
+/// doesn't go through an emulated bevy gamepad, so it doesn't conflict with
+/// real gamepads and different players can be bound to different sticks.
+///
+/// Add a [`TouchStickInputMap`] next to the `ActionState<A>` that should be
+/// driven by touch sticks.
+pub struct TouchStickActionPlugin<A, S = ()> {
+    _marker: PhantomData<(A, S)>,
+}
+
+impl<A, S> Default for TouchStickActionPlugin<A, S> {
+    fn default() -> Self {
+        Self { _marker: default() }
+    }
+}
+
+impl<A: Actionlike, S: StickIdType + PartialEq> Plugin for TouchStickActionPlugin<A, S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            update_action_state::<A, S>
+                .after(InputManagerSystem::Update)
+                .after(TouchStickSystem::UpdateSticks),
+        );
+    }
+}
+
+/// Binds actions to [`TouchStick`] ids
+///
+/// While a bound stick is pressed, the action is pressed and its axis pair is
+/// the stick value, overriding whatever the regular `InputMap` produced. The
+/// action is just pressed once when the stick is pressed and stays held, even
+/// if the `InputMap` releases it. It is released once when the stick is
+/// released, so the same action can still be bound to e.g. a real gamepad
+/// stick in the `InputMap`.
+#[derive(Component, Clone, Debug)]
+pub struct TouchStickInputMap<A: Actionlike, S: StickIdType = ()> {
+    bindings: Vec<(A, S)>,
+}
+
+impl<A: Actionlike, S: StickIdType> Default for TouchStickInputMap<A, S> {
+    fn default() -> Self {
+        Self {
+            bindings: default(),
+        }
+    }
+}
+
+impl<A: Actionlike, S: StickIdType> TouchStickInputMap<A, S> {
+    /// Binds `action` to the [`TouchStick`] with the given id
+    pub fn insert(&mut self, action: A, stick: S) -> &mut Self {
+        self.bindings.push((action, stick));
+        self
+    }
+
+    /// Returns a copy of the map, for use at the end of a builder chain
+    pub fn build(&mut self) -> Self {
+        self.clone()
+    }
+
+    /// Iterates over all bound actions and stick ids
+    pub fn iter(&self) -> impl Iterator<Item = &(A, S)> {
+        self.bindings.iter()
+    }
+}
+
+fn update_action_state<A: Actionlike, S: StickIdType + PartialEq>(
+    mut action_states: Query<(Entity, &mut ActionState<A>, &TouchStickInputMap<A, S>)>,
+    sticks: Query<&TouchStick<S>>,
+    mut pressed_bindings: Local<HashMap<(Entity, usize), Timing>>,
+) {
+    for (entity, mut action_state, input_map) in &mut action_states {
+        for (index, (action, id)) in input_map.iter().enumerate() {
+            let pressed = sticks
+                .iter()
+                .find(|stick| stick.id == *id)
+                .filter(|stick| stick.drag_id.is_some());
+
+            let Some(stick) = pressed else {
+                if pressed_bindings.remove(&(entity, index)).is_some() {
+                    action_state.release(action);
+                }
+                continue;
+            };
+
+            match pressed_bindings.get(&(entity, index)) {
+                // leafwing releases actions the `InputMap` doesn't press, so
+                // undo that instead of pressing the action again every frame
+                Some(timing) => {
+                    if let Some(action_data) = action_state.action_data_mut(action) {
+                        if !action_data.state.pressed() {
+                            action_data.state = ButtonState::Pressed;
+                            action_data.timing = timing.clone();
+                        }
+                    }
+                }
+                None => action_state.press(action),
+            }
+
+            let Some(action_data) = action_state.action_data_mut(action) else {
+                continue;
+            };
+            action_data.value = stick.value.length();
+            action_data.axis_pair = Some(DualAxisData::from_xy(stick.value));
+            pressed_bindings.insert((entity, index), action_data.timing.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{input::InputPlugin, window::PrimaryWindow};
+
+    use super::*;
+    use crate::TouchStickPlugin;
+
+    #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+    enum Action {
+        Move,
+    }
+
+    #[test]
+    fn held_stick_presses_action_once() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            InputPlugin,
+            TouchStickPlugin::<()>::default(),
+            InputManagerPlugin::<Action>::default(),
+            TouchStickActionPlugin::<Action>::default(),
+        ));
+        app.world.spawn((Window::default(), PrimaryWindow));
+        let stick = app.world.spawn(TouchStick::<()>::default()).id();
+        let player = app
+            .world
+            .spawn((
+                InputManagerBundle::<Action> {
+                    action_state: ActionState::default(),
+                    input_map: InputMap::default()
+                        .insert(Action::Move, DualAxis::left_stick())
+                        .build(),
+                },
+                TouchStickInputMap::<Action>::default()
+                    .insert(Action::Move, ())
+                    .build(),
+            ))
+            .id();
+        app.update();
+
+        app.world.get_mut::<TouchStick>(stick).unwrap().drag_id = Some(0);
+        let mut just_pressed = 0;
+        for _ in 0..3 {
+            app.update();
+            let action_state = app.world.get::<ActionState<Action>>(player).unwrap();
+            assert!(action_state.pressed(&Action::Move));
+            assert!(!action_state.just_released(&Action::Move));
+            if action_state.just_pressed(&Action::Move) {
+                just_pressed += 1;
+            }
+        }
+        assert_eq!(just_pressed, 1);
+
+        app.world.get_mut::<TouchStick>(stick).unwrap().drag_id = None;
+        app.update();
+        let action_state = app.world.get::<ActionState<Action>>(player).unwrap();
+        assert!(action_state.just_released(&Action::Move));
+        app.update();
+        let action_state = app.world.get::<ActionState<Action>>(player).unwrap();
+        assert!(action_state.released(&Action::Move));
+        assert!(!action_state.just_released(&Action::Move));
+    }
+}
@@ -41,43 +41,111 @@
 //! }
 //!```
 //!
-use bevy::{prelude::*, reflect::TypePath, ui::UiSystem};
+use bevy::{
+    ecs::system::EntityCommand, input::InputSystem, prelude::*, reflect::TypePath, ui::UiSystem,
+    window::PrimaryWindow,
+};
 use std::{hash::Hash, marker::PhantomData};
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 mod behavior;
 #[cfg(feature = "gamepad_mapping")]
 mod gamepad;
+mod gestures;
+mod history;
 mod input;
+mod keyboard;
+mod layout;
+mod layout_asset;
+#[cfg(feature = "leafwing")]
+mod leafwing;
+mod mirror;
+mod modality;
+#[cfg(feature = "serde")]
+mod preferences;
+mod recovery;
+mod safe_area;
+mod sampling;
+mod sticks;
+mod triggers;
 mod ui;
 
 /// Commonly used exports from this crate
 pub mod prelude {
     #[cfg(feature = "gamepad_mapping")]
     pub use crate::TouchStickGamepadMapping;
-    pub use crate::{TouchStick, TouchStickPlugin, TouchStickType, TouchStickUiBundle};
+    pub use crate::{
+        InputModalityPlugin, TouchStick, TouchStickPlugin, TouchStickType, TouchStickUiBundle,
+        TouchSticks,
+    };
+    #[cfg(feature = "leafwing")]
+    pub use crate::{TouchStickActionPlugin, TouchStickInputMap};
 }
 
 #[cfg(feature = "gamepad_mapping")]
-use crate::gamepad::GamepadMappingPlugin;
+use crate::gamepad::{GamepadConnectionPlugin, GamepadMappingPlugin};
 #[cfg(feature = "gamepad_mapping")]
-pub use crate::gamepad::TouchStickGamepadMapping;
+pub use crate::gamepad::{TouchStickGamepadMapping, TouchStickGamepadPressureMapping};
+#[cfg(feature = "leafwing")]
+pub use crate::leafwing::{TouchStickActionPlugin, TouchStickInputMap};
+#[cfg(feature = "serde")]
+pub use crate::preferences::{
+    LoadTouchStickPreferences, SaveTouchStickPreferences, TouchStickPreference,
+    TouchStickPreferences, TouchStickPreferencesChanged, TouchStickPreferencesFile,
+    TouchStickPreferencesPlugin, TouchStickPreferencesStorage,
+};
 
 pub use crate::{
     behavior::TouchStickType,
-    ui::{TouchStickInteractionArea, TouchStickUiBundle, TouchStickUiKnob, TouchStickUiOutline},
+    gestures::{TouchStickGestures, TouchStickPinchEvent, TouchStickRotateEvent},
+    history::TouchStickHistory,
+    input::TouchStickMouseLeave,
+    keyboard::TouchStickKeyboardEmulation,
+    layout::{
+        ResetTouchLayout, TouchLayout, TouchLayoutEditor, TouchLayoutStick, TouchLayoutVisuals,
+    },
+    layout_asset::TouchLayoutRoot,
+    mirror::TouchStickLayoutMirror,
+    modality::{InputModality, InputModalityPlugin},
+    recovery::TouchStickRecovery,
+    safe_area::{TouchSafeArea, TouchSafeAreaAnchor, TouchSafeAreaSimulation},
+    sampling::{TouchStickEncoding, TouchStickInput, TouchStickQuantization, TouchStickSampler},
+    sticks::TouchSticks,
+    triggers::{TouchStickTriggerEvent, TouchStickTriggerZone, TouchStickTriggers},
+    ui::{
+        TouchStickInteractionArea, TouchStickUiBundle, TouchStickUiDirectionIndicator,
+        TouchStickUiFloatingHint, TouchStickUiKnob, TouchStickUiKnobMotion, TouchStickUiOpacity,
+        TouchStickUiOutline, TouchStickUiStyle,
+    },
 };
 use crate::{
+    history::record_stick_history,
     input::{
         send_drag_events_from_mouse, send_drag_events_from_touch, update_sticks_from_drag_events,
         DragEvent,
     },
+    keyboard::send_drag_events_from_keyboard,
+    layout::TouchLayoutEditorPlugin,
+    layout_asset::TouchLayoutAssetPlugin,
+    mirror::TouchStickLayoutMirrorPlugin,
+    recovery::TouchStickRecoveryPlugin,
+    safe_area::TouchSafeAreaPlugin,
+    sampling::{track_stick_edges, TouchStickEdges},
+    sticks::update_touch_sticks,
+    triggers::update_stick_triggers,
     ui::TouchStickUiPlugin,
 };
 
 /// Pure data, independent of `bevy_ui`
+///
+/// With the `serde` feature, the whole stick can be serialized, or only its
+/// [`TouchStickConfig`] or [`TouchStickState`].
 #[derive(Component, Clone, Debug, Reflect)]
 #[reflect(Component, Default)]
-pub struct TouchStick<S: StickIdType> {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStick<S: StickIdType = ()> {
     /// Type used for identifying this [`TouchStick`]
     pub id: S,
     /// What drag event sequence is currently affecting this [`TouchStick`]
@@ -94,12 +162,23 @@ pub struct TouchStick<S: StickIdType> {
     pub drag_position: Vec2,
     /// Value with maximum magnitude 1
     pub value: Vec2,
+    /// Normalized force of the touch or pen pressing the stick, `0.0..=1.0`
+    ///
+    /// `None` while released, or if the device doesn't report force.
+    pub pressure: Option<f32>,
+    /// `pressure` at or above which [`TouchStick::pressed_hard`] returns `true`
+    pub hard_press_threshold: f32,
     /// In input space (y-down)
     pub interactable_zone: Rect,
     /// In input space, how far to drag before reaching max activation
     pub radius: f32,
+    /// Multiplier for the drag distance, higher values reach max activation sooner
+    pub sensitivity: f32,
     /// Defines the positioning behavior of the [`TouchStick`]
     pub stick_type: TouchStickType,
+    /// Keep the base of [`TouchStickType::Floating`] and [`TouchStickType::Dynamic`]
+    /// sticks at least `radius` inside `interactable_zone`
+    pub clamp_to_zone: bool,
 }
 
 impl<S: StickIdType> Default for TouchStick<S> {
@@ -112,12 +191,16 @@ impl<S: StickIdType> Default for TouchStick<S> {
             drag_start: default(),
             drag_position: default(),
             value: default(),
+            pressure: None,
+            hard_press_threshold: 0.5,
             interactable_zone: Rect {
                 min: Vec2::MIN,
                 max: Vec2::MAX,
             },
             radius: 75.,
+            sensitivity: 1.,
             stick_type: default(),
+            clamp_to_zone: false,
         }
     }
 }
@@ -133,10 +216,210 @@ impl<S: StickIdType> TouchStick<S> {
     pub fn new(id: S) -> Self {
         Self { id, ..default() }
     }
+
+    /// Whether the stick is pressed with at least `hard_press_threshold` pressure
+    pub fn pressed_hard(&self) -> bool {
+        self.pressure
+            .is_some_and(|pressure| pressure >= self.hard_press_threshold)
+    }
+
+    /// `value` with `pressure` as the z axis, `0` if there is no pressure
+    pub fn value_with_pressure(&self) -> Vec3 {
+        self.value.extend(self.pressure.unwrap_or_default())
+    }
+
+    /// Returns the configured part of the stick, e.g. for saving it
+    pub fn config(&self) -> TouchStickConfig<S> {
+        TouchStickConfig {
+            id: self.id.clone(),
+            dead_zone: self.dead_zone,
+            radius: self.radius,
+            sensitivity: self.sensitivity,
+            hard_press_threshold: self.hard_press_threshold,
+            stick_type: self.stick_type,
+            clamp_to_zone: self.clamp_to_zone,
+        }
+    }
+
+    /// Replaces the configured part of the stick, keeping its runtime state
+    pub fn set_config(&mut self, config: TouchStickConfig<S>) {
+        self.id = config.id;
+        self.dead_zone = config.dead_zone;
+        self.radius = config.radius;
+        self.sensitivity = config.sensitivity;
+        self.hard_press_threshold = config.hard_press_threshold;
+        self.stick_type = config.stick_type;
+        self.clamp_to_zone = config.clamp_to_zone;
+    }
+
+    /// Returns the runtime state of the stick, e.g. for sending it over the network
+    pub fn state(&self) -> TouchStickState {
+        TouchStickState {
+            drag_id: self.drag_id,
+            base_position: self.base_position,
+            drag_start: self.drag_start,
+            drag_position: self.drag_position,
+            value: self.value,
+            pressure: self.pressure,
+            interactable_zone: self.interactable_zone,
+        }
+    }
+
+    /// Replaces the runtime state of the stick, keeping its configuration
+    pub fn set_state(&mut self, state: TouchStickState) {
+        self.drag_id = state.drag_id;
+        self.base_position = state.base_position;
+        self.drag_start = state.drag_start;
+        self.drag_position = state.drag_position;
+        self.value = state.value;
+        self.pressure = state.pressure;
+        self.interactable_zone = state.interactable_zone;
+    }
+
+    /// Clamps `position` to `interactable_zone` shrunk by `radius` if
+    /// `clamp_to_zone` is set
+    fn clamp_base(&self, position: Vec2) -> Vec2 {
+        if !self.clamp_to_zone {
+            return position;
+        }
+        let zone = self.interactable_zone;
+        let inset = Vec2::splat(self.radius).min(zone.half_size());
+        position.clamp(zone.min + inset, zone.max - inset)
+    }
+}
+
+impl<S: StickIdType> From<TouchStickConfig<S>> for TouchStick<S> {
+    fn from(config: TouchStickConfig<S>) -> Self {
+        let mut stick = Self::default();
+        stick.set_config(config);
+        stick
+    }
+}
+
+/// The configured part of a [`TouchStick`], without transient drag state
+///
+/// See [`TouchStick::config`].
+#[derive(Clone, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TouchStickConfig<S: StickIdType = ()> {
+    /// See [`TouchStick::id`]
+    pub id: S,
+    /// See [`TouchStick::dead_zone`]
+    pub dead_zone: f32,
+    /// See [`TouchStick::radius`]
+    pub radius: f32,
+    /// See [`TouchStick::sensitivity`]
+    pub sensitivity: f32,
+    /// See [`TouchStick::hard_press_threshold`]
+    pub hard_press_threshold: f32,
+    /// See [`TouchStick::stick_type`]
+    pub stick_type: TouchStickType,
+    /// See [`TouchStick::clamp_to_zone`]
+    pub clamp_to_zone: bool,
+}
+
+impl<S: StickIdType> Default for TouchStickConfig<S> {
+    fn default() -> Self {
+        TouchStick::default().config()
+    }
+}
+
+/// The runtime state of a [`TouchStick`], updated from input
+///
+/// See [`TouchStick::state`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickState {
+    /// See [`TouchStick::drag_id`]
+    pub drag_id: Option<u64>,
+    /// See [`TouchStick::base_position`]
+    pub base_position: Vec2,
+    /// See [`TouchStick::drag_start`]
+    pub drag_start: Vec2,
+    /// See [`TouchStick::drag_position`]
+    pub drag_position: Vec2,
+    /// See [`TouchStick::value`]
+    pub value: Vec2,
+    /// See [`TouchStick::pressure`]
+    pub pressure: Option<f32>,
+    /// See [`TouchStick::interactable_zone`]
+    pub interactable_zone: Rect,
+}
+
+/// Marker component for [`TouchStick`]s that should ignore input
+///
+/// A stick that is disabled while pressed is released.
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickDisabled;
+
+/// Which parts of the crate disabled a stick with [`TouchStickDisabled`]
+///
+/// The marker is only removed once no owner wants the stick disabled anymore.
+/// A marker that was already there when the first owner disabled the stick is
+/// kept as [`TouchStickDisabledBy::USER`].
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub(crate) struct TouchStickDisabledBy(u8);
+
+impl TouchStickDisabledBy {
+    /// Inserted outside of this crate
+    const USER: u8 = 1 << 0;
+    /// [`InputModalityPlugin`] while another input device is used
+    pub(crate) const INPUT_MODALITY: u8 = 1 << 1;
+    /// [`TouchLayoutEditor`] while editing
+    pub(crate) const LAYOUT_EDITOR: u8 = 1 << 2;
+
+    /// Disables the stick on behalf of `owner`
+    pub(crate) fn disable(owner: u8) -> impl EntityCommand {
+        move |entity: Entity, world: &mut World| {
+            let Some(mut entity) = world.get_entity_mut(entity) else {
+                return;
+            };
+            if let Some(mut disabled_by) = entity.get_mut::<TouchStickDisabledBy>() {
+                disabled_by.0 |= owner;
+                return;
+            }
+            let user = if entity.contains::<TouchStickDisabled>() {
+                Self::USER
+            } else {
+                0
+            };
+            entity.insert((TouchStickDisabledBy(owner | user), TouchStickDisabled));
+        }
+    }
+
+    /// Enables the stick again, unless another owner still disables it
+    pub(crate) fn enable(owner: u8) -> impl EntityCommand {
+        move |entity: Entity, world: &mut World| {
+            let Some(mut entity) = world.get_entity_mut(entity) else {
+                return;
+            };
+            let Some(mut disabled_by) = entity.get_mut::<TouchStickDisabledBy>() else {
+                return;
+            };
+            disabled_by.0 &= !owner;
+            if disabled_by.0 & !Self::USER == 0 {
+                let user = disabled_by.0 & Self::USER != 0;
+                entity.remove::<TouchStickDisabledBy>();
+                if !user {
+                    entity.remove::<TouchStickDisabled>();
+                }
+            }
+        }
+    }
 }
 
 /// Plugin holding [`TouchStick`] functionality
-pub struct TouchStickPlugin<S> {
+///
+/// Sticks don't need a dedicated id type. Use `TouchStickPlugin::<()>` and
+/// tell sticks apart by their [`Entity`] (see [`TouchStickEvent::entity`]) or
+/// [`Name`] instead.
+///
+/// Adding the plugin for several id types is supported, systems that don't
+/// depend on the id type are only added once.
+pub struct TouchStickPlugin<S = ()> {
     _marker: PhantomData<S>,
 }
 
@@ -148,22 +431,36 @@ impl<S> Default for TouchStickPlugin<S> {
 
 impl<S: StickIdType> Plugin for TouchStickPlugin<S> {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.register_type::<TouchStickInteractionArea>()
-            .register_type::<TouchStick<S>>()
-            .register_type::<TouchStickType>()
-            .register_type::<TouchStickEventType>()
+        if !app.is_plugin_added::<TouchStickInputPlugin>() {
+            app.add_plugins(TouchStickInputPlugin);
+        }
+
+        app.register_type::<TouchStick<S>>()
+            .register_type::<TouchStickConfig<S>>()
             .add_event::<TouchStickEvent<S>>()
-            .add_event::<DragEvent>()
-            .add_plugins(TouchStickUiPlugin::<S>::default())
+            .add_event::<TouchStickPinchEvent<S>>()
+            .add_event::<TouchStickRotateEvent<S>>()
+            .add_event::<TouchStickTriggerEvent<S>>()
+            .add_plugins((
+                TouchStickUiPlugin::<S>::default(),
+                TouchLayoutAssetPlugin::<S>::default(),
+            ))
+            .init_resource::<TouchStickEdges<S>>()
+            .init_resource::<TouchSticks<S>>()
             .add_systems(
                 PreUpdate,
                 (
-                    // todo: resolve ambiguity
-                    send_drag_events_from_touch.before(update_sticks_from_drag_events::<S>),
-                    send_drag_events_from_mouse.before(update_sticks_from_drag_events::<S>),
+                    send_drag_events_from_keyboard::<S>.in_set(TouchStickSystem::DragEvents),
+                    update_sticks_from_drag_events::<S>.in_set(TouchStickSystem::UpdateSticks),
+                    (
+                        track_stick_edges::<S>,
+                        record_stick_history::<S>,
+                        update_touch_sticks::<S>,
+                        update_stick_triggers::<S>,
+                    )
+                        .after(TouchStickSystem::UpdateSticks),
                 ),
             )
-            .add_systems(PreUpdate, update_sticks_from_drag_events::<S>)
             .add_systems(
                 PostUpdate,
                 map_input_zones_from_ui_nodes::<S>.before(UiSystem::Layout),
@@ -174,6 +471,65 @@ impl<S: StickIdType> Plugin for TouchStickPlugin<S> {
     }
 }
 
+/// Parts of [`TouchStickPlugin`] that are shared between all id types
+struct TouchStickInputPlugin;
+
+impl Plugin for TouchStickInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TouchStickInteractionArea>()
+            .register_type::<TouchStickDisabled>()
+            .register_type::<TouchStickState>()
+            .register_type::<TouchStickGestures>()
+            .register_type::<TouchStickKeyboardEmulation>()
+            .register_type::<TouchStickHistory>()
+            .register_type::<TouchStickTriggers>()
+            .register_type::<TouchStickTriggerZone>()
+            .register_type::<TouchStickMouseLeave>()
+            .init_resource::<TouchStickMouseLeave>()
+            .register_type::<TouchStickInput>()
+            .register_type::<TouchStickEncoding>()
+            .register_type::<TouchStickQuantization>()
+            .init_resource::<TouchStickQuantization>()
+            .register_type::<TouchStickType>()
+            .register_type::<TouchStickEventType>()
+            .add_event::<DragEvent>()
+            .add_event::<CursorMoved>()
+            .add_plugins((
+                TouchSafeAreaPlugin,
+                TouchStickLayoutMirrorPlugin,
+                TouchLayoutEditorPlugin,
+                TouchStickRecoveryPlugin,
+            ))
+            .configure_sets(
+                PreUpdate,
+                TouchStickSystem::DragEvents
+                    .after(InputSystem)
+                    .before(TouchStickSystem::UpdateSticks),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    // todo: resolve ambiguity
+                    send_drag_events_from_touch,
+                    send_drag_events_from_mouse,
+                )
+                    .in_set(TouchStickSystem::DragEvents),
+            );
+
+        #[cfg(feature = "gamepad_mapping")]
+        app.add_plugins(GamepadConnectionPlugin);
+    }
+}
+
+/// Labels for the systems updating [`TouchStick`]s
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TouchStickSystem {
+    /// Reads touch and mouse input and turns it into drag events
+    DragEvents,
+    /// Updates [`TouchStick`] values and sends [`TouchStickEvent`]s
+    UpdateSticks,
+}
+
 /// Type definition for [`TouchStick`] identifier
 pub trait StickIdType:
     Hash + Sync + Send + Clone + Default + Reflect + FromReflect + TypePath + 'static
@@ -190,11 +546,21 @@ fn map_input_zones_from_ui_nodes<S: StickIdType>(
         (&mut TouchStick<S>, &GlobalTransform, &Node),
         With<TouchStickInteractionArea>,
     >,
+    safe_area: Res<TouchSafeArea>,
+    primary_window: Query<&Window, With<PrimaryWindow>>,
 ) {
+    let safe_rect = primary_window
+        .get_single()
+        .ok()
+        .map(|window| safe_area.rect(Vec2::new(window.width(), window.height())));
+
     for (mut touch_stick, transform, node) in &mut interaction_areas {
         let pos = transform.translation().truncate();
         let size = node.size();
-        let interaction_area = Rect::from_center_size(pos, size);
+        let mut interaction_area = Rect::from_center_size(pos, size);
+        if let Some(safe_rect) = safe_rect {
+            interaction_area = interaction_area.intersect(safe_rect);
+        }
         touch_stick.interactable_zone = interaction_area;
     }
 }
@@ -202,6 +568,7 @@ fn map_input_zones_from_ui_nodes<S: StickIdType>(
 /// What action the [`TouchStick`] is experiencing
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
 #[reflect]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TouchStickEventType {
     /// [`TouchStick`] was activated
     Press,
@@ -212,14 +579,19 @@ pub enum TouchStickEventType {
 }
 
 /// Event sent whenever the [`TouchStick`] is interacted.
-#[derive(Event)]
-pub struct TouchStickEvent<S: StickIdType> {
+#[derive(Event, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickEvent<S: StickIdType = ()> {
     /// Identification for joystick that sent this event
     id: S,
+    /// Entity holding the [`TouchStick`] that sent this event
+    entity: Entity,
     /// What interaction did this [`TouchStick`] experience
     event: TouchStickEventType,
     /// [`TouchStick`]
     value: Vec2,
+    /// See [`TouchStick::pressure`]
+    pressure: Option<f32>,
 }
 
 impl<S: StickIdType> TouchStickEvent<S> {
@@ -228,11 +600,24 @@ impl<S: StickIdType> TouchStickEvent<S> {
         self.id.clone()
     }
 
+    /// Returns the entity of the stick that sent the event
+    ///
+    /// Useful for reacting to events from a specific stick entity instead of
+    /// filtering by id.
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
     /// Value of the joystick, maximum length 1
     pub fn value(&self) -> Vec2 {
         self.value
     }
 
+    /// Pressure of the touch, see [`TouchStick::pressure`]
+    pub fn pressure(&self) -> Option<f32> {
+        self.pressure
+    }
+
     /// Return the Type of Joystick Event
     pub fn get_type(&self) -> TouchStickEventType {
         self.event
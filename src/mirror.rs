@@ -0,0 +1,101 @@
+use bevy::{prelude::*, ui::UiSystem};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{safe_area::apply_safe_area_anchors, TouchSafeAreaAnchor, TouchStickInteractionArea};
+
+/// Mirrors the touch stick layout horizontally, e.g. for left-handed players
+///
+/// Toggling `enabled` swaps the horizontal position of all
+/// [`TouchStickUiBundle`](crate::TouchStickUiBundle) nodes at runtime.
+#[derive(Resource, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[reflect(Resource, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickLayoutMirror {
+    /// Whether the layout is mirrored
+    pub enabled: bool,
+    /// Also swap left and right stick axes of
+    /// [`TouchStickGamepadMapping`](crate::TouchStickGamepadMapping)s while mirrored,
+    /// so the stick on the left keeps driving the left gamepad stick
+    pub swap_gamepad_mappings: bool,
+}
+
+impl TouchStickLayoutMirror {
+    /// Whether gamepad mappings should currently be swapped
+    pub fn swaps_gamepad_mappings(&self) -> bool {
+        self.enabled && self.swap_gamepad_mappings
+    }
+}
+
+pub(crate) struct TouchStickLayoutMirrorPlugin;
+
+impl Plugin for TouchStickLayoutMirrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TouchStickLayoutMirror>()
+            .init_resource::<TouchStickLayoutMirror>()
+            .add_systems(
+                PostUpdate,
+                mirror_stick_nodes
+                    .before(apply_safe_area_anchors)
+                    .before(UiSystem::Layout),
+            );
+    }
+}
+
+/// Marker for stick nodes that are currently mirrored
+#[derive(Component)]
+pub(crate) struct Mirrored;
+
+/// Swaps the horizontal position of a node
+pub(crate) fn mirror_style(style: &mut Style) {
+    std::mem::swap(&mut style.left, &mut style.right);
+    std::mem::swap(&mut style.margin.left, &mut style.margin.right);
+}
+
+/// Swaps the horizontal side of an anchor
+pub(crate) fn mirror_anchor(anchor: TouchSafeAreaAnchor) -> TouchSafeAreaAnchor {
+    match anchor {
+        TouchSafeAreaAnchor::None => TouchSafeAreaAnchor::None,
+        TouchSafeAreaAnchor::TopLeft(offset) => TouchSafeAreaAnchor::TopRight(offset),
+        TouchSafeAreaAnchor::TopRight(offset) => TouchSafeAreaAnchor::TopLeft(offset),
+        TouchSafeAreaAnchor::BottomLeft(offset) => TouchSafeAreaAnchor::BottomRight(offset),
+        TouchSafeAreaAnchor::BottomRight(offset) => TouchSafeAreaAnchor::BottomLeft(offset),
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn mirror_stick_nodes(
+    mut commands: Commands,
+    mirror: Res<TouchStickLayoutMirror>,
+    mut nodes: Query<
+        (
+            Entity,
+            &mut Style,
+            Option<&mut TouchSafeAreaAnchor>,
+            Has<Mirrored>,
+        ),
+        With<TouchStickInteractionArea>,
+    >,
+) {
+    for (entity, mut style, anchor, mirrored) in &mut nodes {
+        if mirrored == mirror.enabled {
+            continue;
+        }
+
+        mirror_style(&mut style);
+
+        if let Some(mut anchor) = anchor {
+            let mirrored_anchor = mirror_anchor(*anchor);
+            if *anchor != mirrored_anchor {
+                *anchor = mirrored_anchor;
+            }
+        }
+
+        if mirror.enabled {
+            commands.entity(entity).insert(Mirrored);
+        } else {
+            commands.entity(entity).remove::<Mirrored>();
+        }
+    }
+}
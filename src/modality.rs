@@ -0,0 +1,147 @@
+use bevy::{
+    input::{gamepad::GamepadEvent, keyboard::KeyboardInput, touch::TouchPhase, ButtonState},
+    prelude::*,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    TouchStickDisabledBy, TouchStickInteractionArea, TouchStickKeyboardEmulation, TouchStickSystem,
+};
+
+/// Gamepad axes need to move at least this far to count as gamepad usage,
+/// so stick drift doesn't hide the touch controls.
+const GAMEPAD_AXIS_THRESHOLD: f32 = 0.5;
+
+/// Plugin that hides touch sticks while another input device is used
+///
+/// All [`TouchStickUiBundle`](crate::TouchStickUiBundle) nodes are hidden and
+/// disabled as soon as a physical gamepad or keyboard is used, and shown again
+/// on the next touch. The gamepad emulated by
+/// [`TouchStickGamepadMapping`](crate::TouchStickGamepadMapping) and keys bound
+/// in a [`TouchStickKeyboardEmulation`] are ignored.
+pub struct InputModalityPlugin;
+
+impl Plugin for InputModalityPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<InputModality>()
+            .init_resource::<InputModality>()
+            .add_systems(
+                PreUpdate,
+                (update_input_modality, apply_input_modality)
+                    .chain()
+                    .before(TouchStickSystem::DragEvents),
+            );
+    }
+}
+
+/// The kind of input device the player used most recently
+#[derive(Resource, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum InputModality {
+    /// Touch screen, touch sticks are shown
+    #[default]
+    Touch,
+    /// A physical gamepad, touch sticks are hidden
+    Gamepad,
+    /// A keyboard, touch sticks are hidden
+    Keyboard,
+}
+
+/// Visibility of a stick node before it was hidden by [`InputModalityPlugin`]
+#[derive(Component)]
+struct HiddenByInputModality {
+    visibility: Visibility,
+}
+
+fn update_input_modality(
+    mut modality: ResMut<InputModality>,
+    mut touch_events: EventReader<TouchInput>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut gamepad_events: EventReader<GamepadEvent>,
+    emulated_sticks: Query<&TouchStickKeyboardEmulation>,
+) {
+    let gamepad_used = gamepad_events.read().any(|event| match event {
+        GamepadEvent::Button(event) => {
+            !is_touch_gamepad(event.gamepad) && event.value > GAMEPAD_AXIS_THRESHOLD
+        }
+        GamepadEvent::Axis(event) => {
+            !is_touch_gamepad(event.gamepad) && event.value.abs() > GAMEPAD_AXIS_THRESHOLD
+        }
+        GamepadEvent::Connection(_) => false,
+    });
+    // keys driving emulated sticks are touch stick input
+    let keyboard_used = keyboard_events.read().any(|event| {
+        event.state == ButtonState::Pressed
+            && !emulated_sticks
+                .iter()
+                .any(|emulation| emulation.is_bound(event.key_code))
+    });
+    let touch_used = touch_events
+        .read()
+        .any(|event| event.phase == TouchPhase::Started);
+
+    let new_modality = if touch_used {
+        InputModality::Touch
+    } else if gamepad_used {
+        InputModality::Gamepad
+    } else if keyboard_used {
+        InputModality::Keyboard
+    } else {
+        return;
+    };
+
+    if *modality != new_modality {
+        debug!("input modality changed to {new_modality:?}");
+        *modality = new_modality;
+    }
+}
+
+#[cfg(feature = "gamepad_mapping")]
+fn is_touch_gamepad(gamepad: Gamepad) -> bool {
+    gamepad == crate::gamepad::TOUCH_GAMEPAD
+}
+
+#[cfg(not(feature = "gamepad_mapping"))]
+fn is_touch_gamepad(_gamepad: Gamepad) -> bool {
+    false
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_input_modality(
+    mut commands: Commands,
+    modality: Res<InputModality>,
+    mut shown: Query<
+        (Entity, &mut Visibility),
+        (
+            With<TouchStickInteractionArea>,
+            Without<HiddenByInputModality>,
+        ),
+    >,
+    mut hidden: Query<(Entity, &mut Visibility, &HiddenByInputModality)>,
+) {
+    if *modality == InputModality::Touch {
+        for (entity, mut visibility, hidden_state) in &mut hidden {
+            *visibility = hidden_state.visibility;
+            commands
+                .entity(entity)
+                .remove::<HiddenByInputModality>()
+                .add(TouchStickDisabledBy::enable(
+                    TouchStickDisabledBy::INPUT_MODALITY,
+                ));
+        }
+    } else {
+        for (entity, mut visibility) in &mut shown {
+            commands
+                .entity(entity)
+                .insert(HiddenByInputModality {
+                    visibility: *visibility,
+                })
+                .add(TouchStickDisabledBy::disable(
+                    TouchStickDisabledBy::INPUT_MODALITY,
+                ));
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
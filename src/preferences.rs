@@ -0,0 +1,363 @@
+use std::{
+    fmt, io,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    layout_asset::TouchLayoutSpawned, StickIdType, TouchStick, TouchStickSystem,
+    TouchStickUiOpacity,
+};
+
+/// Plugin that applies [`TouchStickPreferences`] to [`TouchStick`]s and
+/// persists them through a [`TouchStickPreferencesStorage`]
+///
+/// Preferences are loaded at startup, and whenever a
+/// [`LoadTouchStickPreferences`] event is sent. They are saved when a
+/// [`SaveTouchStickPreferences`] event is sent.
+///
+/// When adding the plugin for several id types, give each its own storage.
+pub struct TouchStickPreferencesPlugin<S = ()> {
+    storage: Arc<dyn TouchStickPreferencesStorage>,
+    _marker: PhantomData<S>,
+}
+
+impl<S> Default for TouchStickPreferencesPlugin<S> {
+    fn default() -> Self {
+        Self::new(TouchStickPreferencesFile::default())
+    }
+}
+
+impl<S> TouchStickPreferencesPlugin<S> {
+    /// Creates the plugin with a custom storage
+    pub fn new(storage: impl TouchStickPreferencesStorage) -> Self {
+        Self {
+            storage: Arc::new(storage),
+            _marker: default(),
+        }
+    }
+}
+
+impl<S> Plugin for TouchStickPreferencesPlugin<S>
+where
+    S: StickIdType + PartialEq + Serialize + DeserializeOwned,
+{
+    fn build(&self, app: &mut App) {
+        app.register_type::<TouchStickPreference>()
+            .register_type::<TouchStickPreferences<S>>()
+            .init_resource::<TouchStickPreferences<S>>()
+            .insert_resource(PreferencesStorage::<S> {
+                storage: self.storage.clone(),
+                _marker: default(),
+            })
+            .add_event::<TouchStickPreferencesChanged<S>>()
+            .add_event::<LoadTouchStickPreferences>()
+            .add_event::<SaveTouchStickPreferences>()
+            .add_systems(Startup, load_preferences::<S>)
+            .add_systems(
+                PreUpdate,
+                (
+                    load_preferences::<S>.run_if(on_event::<LoadTouchStickPreferences>()),
+                    apply_preferences::<S>,
+                )
+                    .chain()
+                    .before(TouchStickSystem::UpdateSticks),
+            )
+            .add_systems(
+                Last,
+                save_preferences::<S>.run_if(on_event::<SaveTouchStickPreferences>()),
+            );
+    }
+}
+
+/// Player settings for a single [`TouchStick`]
+///
+/// Only values that are set are applied, the others keep whatever the stick
+/// was spawned with, e.g. from a [`TouchLayout`](crate::TouchLayout).
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[reflect(Default)]
+#[serde(default)]
+pub struct TouchStickPreference {
+    /// Copied to [`TouchStick::sensitivity`]
+    pub sensitivity: Option<f32>,
+    /// Copied to [`TouchStick::dead_zone`]
+    pub dead_zone: Option<f32>,
+    /// Copied to [`TouchStickUiOpacity`]
+    pub opacity: Option<f32>,
+}
+
+/// Player settings for [`TouchStick`]s with id type `S`
+///
+/// Applied to the sticks with a listed id whenever it changes, to new sticks
+/// when they are spawned, and to sticks a [`TouchLayoutRoot`](crate::TouchLayoutRoot)
+/// updates from its layout. Sticks without an entry are left alone.
+#[derive(Resource, Reflect, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct TouchStickPreferences<S: StickIdType = ()> {
+    /// Settings of individual sticks by id
+    pub sticks: Vec<(S, TouchStickPreference)>,
+}
+
+impl<S: StickIdType> Default for TouchStickPreferences<S> {
+    fn default() -> Self {
+        Self { sticks: default() }
+    }
+}
+
+impl<S: StickIdType + PartialEq> TouchStickPreferences<S> {
+    /// Returns the settings of the stick with the given id, if it has any
+    pub fn get(&self, id: &S) -> Option<&TouchStickPreference> {
+        self.sticks
+            .iter()
+            .find(|(stick_id, _)| stick_id == id)
+            .map(|(_, preference)| preference)
+    }
+
+    /// Sets the settings of the stick with the given id
+    pub fn set(&mut self, id: S, preference: TouchStickPreference) {
+        match self.sticks.iter_mut().find(|(stick_id, _)| *stick_id == id) {
+            Some((_, existing)) => *existing = preference,
+            None => self.sticks.push((id, preference)),
+        }
+    }
+}
+
+/// Sent after [`TouchStickPreferences`] changed and were applied to the sticks
+#[derive(Event, Clone, Debug)]
+pub struct TouchStickPreferencesChanged<S: StickIdType = ()> {
+    /// The new preferences
+    pub preferences: TouchStickPreferences<S>,
+}
+
+/// Reloads [`TouchStickPreferences`] from storage
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct LoadTouchStickPreferences;
+
+/// Writes [`TouchStickPreferences`] to storage
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct SaveTouchStickPreferences;
+
+/// Where [`TouchStickPreferences`] are persisted
+///
+/// Preferences are passed as serialized bytes, so implementations only need
+/// to store and return them, e.g. in a file, browser local storage or a
+/// platform key-value store.
+pub trait TouchStickPreferencesStorage: Send + Sync + 'static {
+    /// Returns the stored preferences, or `None` if nothing was saved yet
+    fn load(&self) -> io::Result<Option<Vec<u8>>>;
+
+    /// Stores the preferences
+    fn save(&self, data: &[u8]) -> io::Result<()>;
+}
+
+/// Stores [`TouchStickPreferences`] in a RON file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TouchStickPreferencesFile {
+    /// Path of the file, relative to the working directory
+    pub path: PathBuf,
+}
+
+impl TouchStickPreferencesFile {
+    /// Stores preferences at the given path
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl Default for TouchStickPreferencesFile {
+    fn default() -> Self {
+        Self::new("touch_stick_preferences.ron")
+    }
+}
+
+impl TouchStickPreferencesStorage for TouchStickPreferencesFile {
+    fn load(&self) -> io::Result<Option<Vec<u8>>> {
+        match std::fs::read(&self.path) {
+            Ok(data) => Ok(Some(data)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn save(&self, data: &[u8]) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, data)
+    }
+}
+
+#[derive(Resource)]
+struct PreferencesStorage<S> {
+    storage: Arc<dyn TouchStickPreferencesStorage>,
+    _marker: PhantomData<S>,
+}
+
+#[derive(Debug)]
+enum PreferencesError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+}
+
+impl fmt::Display for PreferencesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "storage error: {error}"),
+            Self::Parse(error) => write!(f, "could not parse preferences: {error}"),
+            Self::Serialize(error) => write!(f, "could not serialize preferences: {error}"),
+        }
+    }
+}
+
+fn load_preferences<S>(
+    storage: Res<PreferencesStorage<S>>,
+    mut preferences: ResMut<TouchStickPreferences<S>>,
+) where
+    S: StickIdType + PartialEq + DeserializeOwned,
+{
+    let loaded = storage
+        .storage
+        .load()
+        .map_err(PreferencesError::Io)
+        .and_then(|data| {
+            data.map(|data| ron::de::from_bytes(&data).map_err(PreferencesError::Parse))
+                .transpose()
+        });
+
+    match loaded {
+        Ok(Some(loaded)) => *preferences = loaded,
+        Ok(None) => {}
+        Err(error) => error!("failed to load touch stick preferences, {error}"),
+    }
+}
+
+fn save_preferences<S>(
+    storage: Res<PreferencesStorage<S>>,
+    preferences: Res<TouchStickPreferences<S>>,
+) where
+    S: StickIdType + Serialize,
+{
+    let saved = ron::ser::to_string_pretty(&*preferences, default())
+        .map_err(PreferencesError::Serialize)
+        .and_then(|data| {
+            storage
+                .storage
+                .save(data.as_bytes())
+                .map_err(PreferencesError::Io)
+        });
+
+    if let Err(error) = saved {
+        error!("failed to save touch stick preferences, {error}");
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_preferences<S: StickIdType + PartialEq>(
+    mut commands: Commands,
+    preferences: Res<TouchStickPreferences<S>>,
+    mut sticks: Query<(Entity, &mut TouchStick<S>, Option<Ref<TouchLayoutSpawned>>)>,
+    mut changed_events: EventWriter<TouchStickPreferencesChanged<S>>,
+) {
+    let changed = preferences.is_changed();
+    for (entity, mut stick, layout) in &mut sticks {
+        // a layout update overwrites the stick config
+        let layout_changed = layout.is_some_and(|layout| layout.is_changed());
+        if !changed && !stick.is_added() && !layout_changed {
+            continue;
+        }
+        let Some(&preference) = preferences.get(&stick.id) else {
+            continue;
+        };
+        if let Some(sensitivity) = preference.sensitivity {
+            stick.sensitivity = sensitivity;
+        }
+        if let Some(dead_zone) = preference.dead_zone {
+            stick.dead_zone = dead_zone;
+        }
+        if let Some(opacity) = preference.opacity {
+            commands.entity(entity).insert(TouchStickUiOpacity(opacity));
+        }
+    }
+
+    if changed {
+        changed_events.send(TouchStickPreferencesChanged {
+            preferences: preferences.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{asset::AssetPlugin, input::InputPlugin, window::PrimaryWindow};
+
+    use super::*;
+    use crate::{TouchLayout, TouchLayoutRoot, TouchLayoutStick, TouchStickPlugin};
+
+    struct NoStorage;
+
+    impl TouchStickPreferencesStorage for NoStorage {
+        fn load(&self) -> io::Result<Option<Vec<u8>>> {
+            Ok(None)
+        }
+
+        fn save(&self, _data: &[u8]) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn reapplies_after_layout_update() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            InputPlugin,
+            TouchStickPlugin::<()>::default(),
+            TouchStickPreferencesPlugin::<()>::new(NoStorage),
+        ));
+        app.world.spawn((Window::default(), PrimaryWindow));
+        app.world.resource_mut::<TouchStickPreferences>().set(
+            (),
+            TouchStickPreference {
+                dead_zone: Some(0.3),
+                ..default()
+            },
+        );
+        let mut layout = TouchLayout::default();
+        layout.insert(TouchLayoutStick {
+            name: "left".into(),
+            dead_zone: 0.1,
+            ..default()
+        });
+        let layout = app.world.resource_mut::<Assets<TouchLayout>>().add(layout);
+        app.world.spawn(TouchLayoutRoot::<()>::new(layout.clone()));
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let dead_zone = |app: &mut App| {
+            let mut sticks = app.world.query::<&TouchStick>();
+            sticks.single(&app.world).dead_zone
+        };
+        assert_eq!(dead_zone(&mut app), 0.3);
+
+        app.world
+            .resource_mut::<Assets<TouchLayout>>()
+            .get_mut(&layout)
+            .unwrap()
+            .sticks[0]
+            .radius = 50.;
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(dead_zone(&mut app), 0.3);
+    }
+}
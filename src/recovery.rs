@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::event::ManualEventReader,
+    prelude::*,
+    utils::HashMap,
+    window::{ApplicationLifetime, WindowFocused},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input::{send_drag_events_from_mouse, send_drag_events_from_touch, DragEvent},
+    TouchStickSystem,
+};
+
+/// Releases sticks whose touch was lost without an `Ended` event
+///
+/// Some platforms drop the end of a touch, e.g. when the app loses focus, which
+/// would leave a [`TouchStick`](crate::TouchStick) pressed forever. Lost drags
+/// are ended as if the finger was lifted.
+///
+/// Drags emulated with [`TouchStickKeyboardEmulation`](crate::TouchStickKeyboardEmulation)
+/// end with their keys and are left alone.
+#[derive(Resource, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Resource, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickRecovery {
+    /// End all drags when a window loses focus
+    pub release_on_focus_lost: bool,
+    /// End all drags when the app is suspended
+    pub release_on_suspend: bool,
+    /// End touch drags that are no longer pressed according to bevy's [`Touches`]
+    pub release_missing_touches: bool,
+    /// End drags that haven't moved for this long, `None` to keep them forever
+    ///
+    /// Touches that are held still don't send events, so this should be well
+    /// above the time a player might rest a finger on a stick.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for TouchStickRecovery {
+    fn default() -> Self {
+        Self {
+            release_on_focus_lost: true,
+            release_on_suspend: true,
+            release_missing_touches: true,
+            timeout: None,
+        }
+    }
+}
+
+pub(crate) struct TouchStickRecoveryPlugin;
+
+impl Plugin for TouchStickRecoveryPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TouchStickRecovery>()
+            .init_resource::<TouchStickRecovery>()
+            .add_event::<WindowFocused>()
+            .add_event::<ApplicationLifetime>()
+            .add_systems(
+                PreUpdate,
+                end_lost_drags
+                    .after(send_drag_events_from_touch)
+                    .after(send_drag_events_from_mouse)
+                    .in_set(TouchStickSystem::DragEvents),
+            );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn end_lost_drags(
+    recovery: Res<TouchStickRecovery>,
+    time: Res<Time<Real>>,
+    touches: Option<Res<Touches>>,
+    mouse_buttons: Option<Res<ButtonInput<MouseButton>>>,
+    mut focus_events: EventReader<WindowFocused>,
+    mut lifetime_events: EventReader<ApplicationLifetime>,
+    mut drag_events: ResMut<Events<DragEvent>>,
+    mut drag_reader: Local<ManualEventReader<DragEvent>>,
+    mut last_seen: Local<HashMap<u64, Duration>>,
+) {
+    let now = time.elapsed();
+    for event in drag_reader.read(&drag_events) {
+        if event.is_emulated() {
+            continue;
+        }
+        match *event {
+            DragEvent::Start { id, .. } | DragEvent::Drag { id, .. } => {
+                last_seen.insert(id, now);
+            }
+            DragEvent::End { id } => {
+                last_seen.remove(&id);
+            }
+        }
+    }
+
+    let focus_lost = focus_events.read().any(|event| !event.focused);
+    let suspended = lifetime_events
+        .read()
+        .any(|event| *event == ApplicationLifetime::Suspended);
+    let release_all = (focus_lost && recovery.release_on_focus_lost)
+        || (suspended && recovery.release_on_suspend);
+
+    let lost = last_seen
+        .iter()
+        .filter(|(&id, &seen)| {
+            release_all
+                || recovery
+                    .timeout
+                    .is_some_and(|timeout| now.saturating_sub(seen) > timeout)
+                || (recovery.release_missing_touches
+                    && is_missing_touch(id, touches.as_deref(), mouse_buttons.as_deref()))
+        })
+        .map(|(&id, _)| id)
+        .collect::<Vec<_>>();
+
+    for id in lost {
+        debug!("ending lost drag {id}");
+        last_seen.remove(&id);
+        drag_events.send(DragEvent::End { id });
+    }
+}
+
+/// Whether `id` belongs to a touch that bevy no longer considers pressed
+fn is_missing_touch(
+    id: u64,
+    touches: Option<&Touches>,
+    mouse_buttons: Option<&ButtonInput<MouseButton>>,
+) -> bool {
+    let Some(touches) = touches else {
+        return false;
+    };
+    // the mouse shares id 0 with the first touch on some platforms
+    if id == 0 && mouse_buttons.is_some_and(|buttons| buttons.pressed(MouseButton::Left)) {
+        return false;
+    }
+    touches.get_pressed(id).is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{input::InputPlugin, window::PrimaryWindow};
+
+    use super::*;
+    use crate::{TouchStick, TouchStickKeyboardEmulation, TouchStickPlugin};
+
+    #[test]
+    fn keeps_emulated_drags_on_focus_loss() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            InputPlugin,
+            TouchStickPlugin::<()>::default(),
+        ));
+        let window = app.world.spawn((Window::default(), PrimaryWindow)).id();
+        let stick = app
+            .world
+            .spawn((
+                TouchStick::<()>::default(),
+                TouchStickKeyboardEmulation::wasd(),
+            ))
+            .id();
+
+        app.world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyD);
+        app.update();
+        app.world.send_event(WindowFocused {
+            window,
+            focused: false,
+        });
+        app.update();
+
+        let stick = app.world.get::<TouchStick>(stick).unwrap();
+        assert!(stick.drag_id.is_some());
+        assert_eq!(stick.value, Vec2::X);
+    }
+}
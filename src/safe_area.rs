@@ -0,0 +1,229 @@
+use bevy::{
+    prelude::*,
+    ui::{FocusPolicy, UiSystem},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Insets of the screen area that is safe from notches, rounded corners and
+/// system gestures, in logical pixels
+///
+/// Defaults to no insets. Set it from the app, e.g. from a platform hook
+/// reading the window insets, or use [`TouchSafeAreaSimulation`] to try out
+/// insets on desktop.
+#[derive(Resource, Reflect, Clone, Copy, Debug, Default, PartialEq)]
+#[reflect(Resource, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchSafeArea {
+    /// Inset from the top edge of the window
+    pub top: f32,
+    /// Inset from the right edge of the window
+    pub right: f32,
+    /// Inset from the bottom edge of the window
+    pub bottom: f32,
+    /// Inset from the left edge of the window
+    pub left: f32,
+}
+
+impl TouchSafeArea {
+    /// Returns the safe part of a window of the given size, in input space (y-down)
+    pub fn rect(&self, window_size: Vec2) -> Rect {
+        Rect::new(
+            self.left,
+            self.top,
+            window_size.x - self.right,
+            window_size.y - self.bottom,
+        )
+    }
+}
+
+/// Overrides [`TouchSafeArea`] for testing without a device
+///
+/// While this resource exists, its insets are copied into [`TouchSafeArea`]
+/// every frame, and the unsafe parts of the window are covered by an overlay.
+#[derive(Resource, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Resource)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchSafeAreaSimulation {
+    /// The simulated insets
+    pub insets: TouchSafeArea,
+    /// Color of the overlay covering the unsafe area, `None` to disable it
+    pub overlay_color: Option<Color>,
+}
+
+impl Default for TouchSafeAreaSimulation {
+    fn default() -> Self {
+        Self {
+            // roughly a phone in landscape with a notch on the left
+            insets: TouchSafeArea {
+                top: 0.,
+                right: 0.,
+                bottom: 21.,
+                left: 47.,
+            },
+            overlay_color: Some(Color::rgba(1., 0., 0., 0.25)),
+        }
+    }
+}
+
+/// Positions a [`TouchStick`](crate::TouchStick) node relative to a corner of
+/// the [`TouchSafeArea`]
+///
+/// Overrides the `left`, `right`, `top` and `bottom` of the node's [`Style`],
+/// which should be absolutely positioned in a node covering the window.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default, PartialEq)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TouchSafeAreaAnchor {
+    /// Keep the node's [`Style`] as is
+    #[default]
+    None,
+    /// Offset from the top left corner of the safe area
+    TopLeft(Vec2),
+    /// Offset from the top right corner of the safe area
+    TopRight(Vec2),
+    /// Offset from the bottom left corner of the safe area
+    BottomLeft(Vec2),
+    /// Offset from the bottom right corner of the safe area
+    BottomRight(Vec2),
+}
+
+pub(crate) struct TouchSafeAreaPlugin;
+
+impl Plugin for TouchSafeAreaPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TouchSafeArea>()
+            .register_type::<TouchSafeAreaSimulation>()
+            .register_type::<TouchSafeAreaAnchor>()
+            .init_resource::<TouchSafeArea>()
+            .add_systems(
+                PostUpdate,
+                (
+                    simulate_safe_area.run_if(resource_exists::<TouchSafeAreaSimulation>),
+                    update_simulation_overlay,
+                    apply_safe_area_anchors,
+                )
+                    .chain()
+                    .before(UiSystem::Layout),
+            );
+    }
+}
+
+pub(crate) fn apply_safe_area_anchors(
+    safe_area: Res<TouchSafeArea>,
+    mut anchored: Query<(Ref<TouchSafeAreaAnchor>, &mut Style)>,
+) {
+    for (anchor, mut style) in &mut anchored {
+        if !safe_area.is_changed() && !anchor.is_changed() {
+            continue;
+        }
+
+        let (horizontal, vertical, offset) = match *anchor {
+            TouchSafeAreaAnchor::None => continue,
+            TouchSafeAreaAnchor::TopLeft(offset) => (Edge::Start, Edge::Start, offset),
+            TouchSafeAreaAnchor::TopRight(offset) => (Edge::End, Edge::Start, offset),
+            TouchSafeAreaAnchor::BottomLeft(offset) => (Edge::Start, Edge::End, offset),
+            TouchSafeAreaAnchor::BottomRight(offset) => (Edge::End, Edge::End, offset),
+        };
+
+        style.position_type = PositionType::Absolute;
+        (style.left, style.right) = match horizontal {
+            Edge::Start => (Val::Px(safe_area.left + offset.x), Val::Auto),
+            Edge::End => (Val::Auto, Val::Px(safe_area.right + offset.x)),
+        };
+        (style.top, style.bottom) = match vertical {
+            Edge::Start => (Val::Px(safe_area.top + offset.y), Val::Auto),
+            Edge::End => (Val::Auto, Val::Px(safe_area.bottom + offset.y)),
+        };
+    }
+}
+
+enum Edge {
+    Start,
+    End,
+}
+
+fn simulate_safe_area(
+    simulation: Res<TouchSafeAreaSimulation>,
+    mut safe_area: ResMut<TouchSafeArea>,
+) {
+    if *safe_area != simulation.insets {
+        *safe_area = simulation.insets;
+    }
+}
+
+/// Marker for the nodes covering the unsafe area in [`TouchSafeAreaSimulation`]
+#[derive(Component)]
+struct SimulationOverlay;
+
+fn update_simulation_overlay(
+    mut commands: Commands,
+    simulation: Option<Res<TouchSafeAreaSimulation>>,
+    overlays: Query<Entity, With<SimulationOverlay>>,
+) {
+    let overlay = simulation.as_ref().and_then(|simulation| {
+        simulation
+            .overlay_color
+            .map(|color| (simulation.insets, color))
+    });
+    let changed = simulation
+        .as_ref()
+        .map_or(!overlays.is_empty(), |simulation| simulation.is_changed());
+    if !changed {
+        return;
+    }
+
+    for entity in &overlays {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let Some((insets, color)) = overlay else {
+        return;
+    };
+
+    let full = Val::Percent(100.);
+    let strips = [
+        Style {
+            top: Val::Px(0.),
+            width: full,
+            height: Val::Px(insets.top),
+            ..default()
+        },
+        Style {
+            bottom: Val::Px(0.),
+            width: full,
+            height: Val::Px(insets.bottom),
+            ..default()
+        },
+        Style {
+            left: Val::Px(0.),
+            width: Val::Px(insets.left),
+            height: full,
+            ..default()
+        },
+        Style {
+            right: Val::Px(0.),
+            width: Val::Px(insets.right),
+            height: full,
+            ..default()
+        },
+    ];
+
+    for style in strips {
+        commands.spawn((
+            Name::new("TouchSafeAreaSimulationOverlay"),
+            SimulationOverlay,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    ..style
+                },
+                background_color: color.into(),
+                focus_policy: FocusPolicy::Pass,
+                z_index: ZIndex::Global(i32::MAX),
+                ..default()
+            },
+        ));
+    }
+}
@@ -0,0 +1,352 @@
+use std::{f32::consts::TAU, marker::PhantomData};
+
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{StickIdType, TouchStick, TouchStickEvent, TouchStickEventType};
+
+/// A quantized snapshot of a [`TouchStick`], taken with [`TouchStickSampler`]
+///
+/// Plain old data without padding, so it can be cast to bytes or used as
+/// (part of) the input type of rollback networking libraries like `bevy_ggrs`.
+/// The value is stored with [`TouchStickEncoding::Axes`], so `x` and `y` can be
+/// read directly and decoding doesn't depend on a configured encoding.
+///
+/// There is no polar variant. To send the value with
+/// [`TouchStickEncoding::Polar`], e.g. because [`TouchStickQuantization`] uses
+/// it, send `TouchStickEncoding::Polar.encode(stick.value)` along with the
+/// `flags` of a sample instead of `x` and `y`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickInput {
+    /// Horizontal value, `-127..=127` maps to `-1.0..=1.0`
+    pub x: i8,
+    /// Vertical value, `-127..=127` maps to `-1.0..=1.0`
+    pub y: i8,
+    /// Combination of [`TouchStickInput::PRESSED`], [`TouchStickInput::JUST_PRESSED`]
+    /// and [`TouchStickInput::JUST_RELEASED`]
+    pub flags: u8,
+}
+
+impl TouchStickInput {
+    /// The stick is pressed at the time of sampling
+    pub const PRESSED: u8 = 1 << 0;
+    /// The stick was pressed since the previous sample
+    pub const JUST_PRESSED: u8 = 1 << 1;
+    /// The stick was released since the previous sample
+    pub const JUST_RELEASED: u8 = 1 << 2;
+
+    /// Quantizes a stick value
+    pub fn from_value(value: Vec2, flags: u8) -> Self {
+        let [x, y] = TouchStickEncoding::Axes.encode(value);
+        Self {
+            x: x as i8,
+            y: y as i8,
+            flags,
+        }
+    }
+
+    /// The quantized stick value
+    pub fn value(&self) -> Vec2 {
+        TouchStickEncoding::Axes.decode([self.x as u8, self.y as u8])
+    }
+
+    /// Whether the stick is pressed
+    pub fn pressed(&self) -> bool {
+        self.flags & Self::PRESSED != 0
+    }
+
+    /// Whether the stick was pressed since the previous sample
+    ///
+    /// Also set for taps that were released before the sample was taken.
+    pub fn just_pressed(&self) -> bool {
+        self.flags & Self::JUST_PRESSED != 0
+    }
+
+    /// Whether the stick was released since the previous sample
+    pub fn just_released(&self) -> bool {
+        self.flags & Self::JUST_RELEASED != 0
+    }
+
+    /// Whether the stick was pressed and released again since the previous sample
+    pub fn tapped(&self) -> bool {
+        self.just_pressed() && self.just_released() && !self.pressed()
+    }
+
+    /// The input as bytes
+    pub fn to_bytes(self) -> [u8; 3] {
+        [self.x as u8, self.y as u8, self.flags]
+    }
+
+    /// Reads an input from bytes written by [`TouchStickInput::to_bytes`]
+    pub fn from_bytes(bytes: [u8; 3]) -> Self {
+        Self {
+            x: bytes[0] as i8,
+            y: bytes[1] as i8,
+            flags: bytes[2],
+        }
+    }
+}
+
+/// Two byte encodings of [`TouchStick::value`], e.g. for sending it over the network
+///
+/// Values outside the unit circle are clamped. For all bytes returned
+/// by [`TouchStickEncoding::encode`], decoding and encoding again returns the
+/// same bytes, so [`TouchStickEncoding::quantize`] is idempotent and a
+/// quantized value survives any number of round trips unchanged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TouchStickEncoding {
+    /// One `i8` per axis, `-127..=127` maps to `-1.0..=1.0`
+    ///
+    /// Exact for the cardinal directions. Each axis is clamped separately, so
+    /// a quantized diagonal may be up to one step longer than 1.
+    #[default]
+    Axes,
+    /// Angle and magnitude as one `u8` each
+    ///
+    /// The angle has 256 steps counter-clockwise from the positive x axis, the
+    /// magnitude has 255 steps. Keeps the length of the value exact to one
+    /// step in every direction, and the angle is `0` for a zero value.
+    Polar,
+}
+
+impl TouchStickEncoding {
+    /// Encodes a stick value
+    pub fn encode(self, value: Vec2) -> [u8; 2] {
+        match self {
+            Self::Axes => [encode_axis(value.x), encode_axis(value.y)],
+            Self::Polar => {
+                let value = value.clamp_length_max(1.);
+                let magnitude = (value.length() * u8::MAX as f32).round() as u8;
+                if magnitude == 0 {
+                    return [0, 0];
+                }
+                let turns = value.y.atan2(value.x) / TAU;
+                let angle = (turns * ANGLE_STEPS).round().rem_euclid(ANGLE_STEPS) as u8;
+                [angle, magnitude]
+            }
+        }
+    }
+
+    /// Decodes a stick value encoded with [`TouchStickEncoding::encode`]
+    pub fn decode(self, bytes: [u8; 2]) -> Vec2 {
+        match self {
+            Self::Axes => Vec2::new(decode_axis(bytes[0]), decode_axis(bytes[1])),
+            Self::Polar => {
+                let [angle, magnitude] = bytes;
+                if magnitude == 0 {
+                    return Vec2::ZERO;
+                }
+                let angle = angle as f32 / ANGLE_STEPS * TAU;
+                Vec2::from_angle(angle) * (magnitude as f32 / u8::MAX as f32)
+            }
+        }
+    }
+
+    /// Rounds a stick value to the nearest value this encoding can represent
+    pub fn quantize(self, value: Vec2) -> Vec2 {
+        self.decode(self.encode(value))
+    }
+}
+
+const ANGLE_STEPS: f32 = 256.;
+
+fn encode_axis(value: f32) -> u8 {
+    (value.clamp(-1., 1.) * i8::MAX as f32).round() as i8 as u8
+}
+
+fn decode_axis(byte: u8) -> f32 {
+    (byte as i8).max(-i8::MAX) as f32 / i8::MAX as f32
+}
+
+/// Quantizes [`TouchStick::value`] whenever it is updated from input
+///
+/// Use the same encoding as for sending input over the network, so local and
+/// remote simulations see identical values. The value is quantized before
+/// the dead zone is checked and [`TouchStickEvent`]s are sent, so events
+/// carry quantized values too. Disabled by default.
+#[derive(Resource, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[reflect(Resource, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickQuantization {
+    /// The encoding to quantize to, `None` to keep full precision
+    pub encoding: Option<TouchStickEncoding>,
+}
+
+impl TouchStickQuantization {
+    /// Quantizes `value` with the configured encoding, if any
+    pub fn quantize(&self, value: Vec2) -> Vec2 {
+        match self.encoding {
+            Some(encoding) => encoding.quantize(value),
+            None => value,
+        }
+    }
+}
+
+/// Press and release edges of sticks since they were last sampled
+#[derive(Resource)]
+pub(crate) struct TouchStickEdges<S> {
+    edges: HashMap<Entity, u8>,
+    _marker: PhantomData<S>,
+}
+
+impl<S> Default for TouchStickEdges<S> {
+    fn default() -> Self {
+        Self {
+            edges: default(),
+            _marker: default(),
+        }
+    }
+}
+
+pub(crate) fn track_stick_edges<S: StickIdType>(
+    mut edges: ResMut<TouchStickEdges<S>>,
+    mut stick_events: EventReader<TouchStickEvent<S>>,
+    mut removed_sticks: RemovedComponents<TouchStick<S>>,
+) {
+    // sticks are respawned e.g. when a layout asset is reloaded
+    for entity in removed_sticks.read() {
+        edges.edges.remove(&entity);
+    }
+
+    for event in stick_events.read() {
+        let flag = match event.get_type() {
+            TouchStickEventType::Press => TouchStickInput::JUST_PRESSED,
+            TouchStickEventType::Release => TouchStickInput::JUST_RELEASED,
+            TouchStickEventType::Drag => continue,
+        };
+        *edges.edges.entry(event.entity()).or_default() |= flag;
+    }
+}
+
+/// Takes [`TouchStickInput`] snapshots of sticks, e.g. once per fixed tick
+///
+/// Use it in the system that collects input for a fixed timestep or rollback
+/// simulation. Presses and releases are remembered until the stick is sampled,
+/// so short taps between two ticks aren't lost. Sampling a stick consumes
+/// them, so sample each stick once per tick.
+#[derive(SystemParam)]
+pub struct TouchStickSampler<'w, 's, S: StickIdType = ()> {
+    sticks: Query<'w, 's, (Entity, &'static TouchStick<S>)>,
+    edges: ResMut<'w, TouchStickEdges<S>>,
+}
+
+impl<'w, 's, S: StickIdType> TouchStickSampler<'w, 's, S> {
+    /// Samples the stick on the given entity
+    pub fn sample_entity(&mut self, entity: Entity) -> Option<TouchStickInput> {
+        let (_, stick) = self.sticks.get(entity).ok()?;
+        Some(take_edges(&mut self.edges, entity, stick))
+    }
+
+    /// Samples all sticks, in no particular order
+    pub fn sample_all(&mut self) -> Vec<(Entity, S, TouchStickInput)> {
+        let mut samples = Vec::new();
+        for (entity, stick) in &self.sticks {
+            let input = take_edges(&mut self.edges, entity, stick);
+            samples.push((entity, stick.id.clone(), input));
+        }
+        samples
+    }
+}
+
+impl<'w, 's, S: StickIdType + PartialEq> TouchStickSampler<'w, 's, S> {
+    /// Samples the first stick with the given id, or returns a released input
+    /// if there is none
+    pub fn sample(&mut self, id: &S) -> TouchStickInput {
+        match self.sticks.iter().find(|(_, stick)| stick.id == *id) {
+            Some((entity, stick)) => take_edges(&mut self.edges, entity, stick),
+            None => TouchStickInput::default(),
+        }
+    }
+}
+
+fn take_edges<S: StickIdType>(
+    edges: &mut TouchStickEdges<S>,
+    entity: Entity,
+    stick: &TouchStick<S>,
+) -> TouchStickInput {
+    let mut flags = edges.edges.remove(&entity).unwrap_or_default();
+    if stick.drag_id.is_some() {
+        flags |= TouchStickInput::PRESSED;
+    }
+    TouchStickInput::from_value(stick.value, flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENCODINGS: [TouchStickEncoding; 2] =
+        [TouchStickEncoding::Axes, TouchStickEncoding::Polar];
+
+    fn all_bytes() -> impl Iterator<Item = [u8; 2]> {
+        (0..=u8::MAX).flat_map(|first| (0..=u8::MAX).map(move |second| [first, second]))
+    }
+
+    /// The bytes [`TouchStickEncoding::encode`] returns for the decoded value
+    fn canonical(encoding: TouchStickEncoding, bytes: [u8; 2]) -> [u8; 2] {
+        match encoding {
+            // -128 decodes to -1, which is encoded as -127
+            TouchStickEncoding::Axes => bytes.map(|byte| if byte == 0x80 { 0x81 } else { byte }),
+            // the angle of a zero value is 0
+            TouchStickEncoding::Polar if bytes[1] == 0 => [0, 0],
+            TouchStickEncoding::Polar => bytes,
+        }
+    }
+
+    #[test]
+    fn encoded_values_round_trip() {
+        for encoding in ENCODINGS {
+            for bytes in all_bytes() {
+                let decoded = encoding.decode(bytes);
+                assert!(decoded.is_finite(), "{encoding:?} {bytes:?}");
+                assert_eq!(
+                    encoding.encode(decoded),
+                    canonical(encoding, bytes),
+                    "{encoding:?} {bytes:?} decoded to {decoded}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn quantize_is_idempotent() {
+        for encoding in ENCODINGS {
+            for x in -150..=150 {
+                for y in -150..=150 {
+                    let value = Vec2::new(x as f32, y as f32) / 100.;
+                    let quantized = encoding.quantize(value);
+                    assert_eq!(
+                        encoding.quantize(quantized),
+                        quantized,
+                        "{encoding:?} {value}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn input_round_trips_through_bytes() {
+        for [x, y] in all_bytes() {
+            for flags in [0, TouchStickInput::PRESSED | TouchStickInput::JUST_RELEASED] {
+                let input = TouchStickInput {
+                    x: x as i8,
+                    y: y as i8,
+                    flags,
+                };
+                assert_eq!(TouchStickInput::from_bytes(input.to_bytes()), input);
+            }
+        }
+        for bytes in all_bytes() {
+            let value = TouchStickEncoding::Axes.decode(bytes);
+            let input = TouchStickInput::from_value(value, TouchStickInput::PRESSED);
+            assert_eq!(input.value(), value);
+            assert!(input.pressed());
+        }
+    }
+}
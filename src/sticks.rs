@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+
+use crate::{StickIdType, TouchStick, TouchStickEvent, TouchStickEventType};
+
+/// State of all [`TouchStick`]s with id type `S`, polled by id
+///
+/// Updated once per frame after [`TouchStickSystem::UpdateSticks`](crate::TouchStickSystem::UpdateSticks),
+/// similar to [`ButtonInput`] and [`Axis`]. If several sticks share an id, the
+/// first one found is used. Ids without a stick read as released and zero.
+#[derive(Resource, Clone, Debug)]
+pub struct TouchSticks<S: StickIdType = ()> {
+    sticks: Vec<StickStatus<S>>,
+}
+
+#[derive(Clone, Debug)]
+struct StickStatus<S> {
+    id: S,
+    entity: Entity,
+    pressed: bool,
+    just_pressed: bool,
+    just_released: bool,
+    value: Vec2,
+}
+
+impl<S: StickIdType> Default for TouchSticks<S> {
+    fn default() -> Self {
+        Self { sticks: default() }
+    }
+}
+
+impl<S: StickIdType + PartialEq> TouchSticks<S> {
+    /// Whether the stick is held
+    pub fn pressed(&self, id: &S) -> bool {
+        self.get(id).is_some_and(|stick| stick.pressed)
+    }
+
+    /// Whether the stick was pressed this frame
+    ///
+    /// Also true for taps that were released in the same frame.
+    pub fn just_pressed(&self, id: &S) -> bool {
+        self.get(id).is_some_and(|stick| stick.just_pressed)
+    }
+
+    /// Whether the stick was released this frame
+    pub fn just_released(&self, id: &S) -> bool {
+        self.get(id).is_some_and(|stick| stick.just_released)
+    }
+
+    /// The value of the stick, see [`TouchStick::value`]
+    pub fn value(&self, id: &S) -> Vec2 {
+        self.get(id).map(|stick| stick.value).unwrap_or_default()
+    }
+
+    /// Angle of the value in radians, counter-clockwise from the positive x axis
+    ///
+    /// `0` while the value is zero.
+    pub fn angle(&self, id: &S) -> f32 {
+        let value = self.value(id);
+        if value == Vec2::ZERO {
+            return 0.;
+        }
+        value.y.atan2(value.x)
+    }
+
+    /// Length of the value, `0.0..=1.0`
+    pub fn magnitude(&self, id: &S) -> f32 {
+        self.value(id).length()
+    }
+
+    /// The entity of the stick
+    pub fn entity(&self, id: &S) -> Option<Entity> {
+        self.get(id).map(|stick| stick.entity)
+    }
+
+    fn get(&self, id: &S) -> Option<&StickStatus<S>> {
+        self.sticks.iter().find(|stick| stick.id == *id)
+    }
+}
+
+impl<S: StickIdType> TouchSticks<S> {
+    /// Ids of all held sticks
+    pub fn get_pressed(&self) -> impl Iterator<Item = &S> {
+        self.sticks
+            .iter()
+            .filter(|stick| stick.pressed)
+            .map(|stick| &stick.id)
+    }
+
+    /// Ids of all sticks pressed this frame
+    pub fn get_just_pressed(&self) -> impl Iterator<Item = &S> {
+        self.sticks
+            .iter()
+            .filter(|stick| stick.just_pressed)
+            .map(|stick| &stick.id)
+    }
+
+    /// Ids of all sticks released this frame
+    pub fn get_just_released(&self) -> impl Iterator<Item = &S> {
+        self.sticks
+            .iter()
+            .filter(|stick| stick.just_released)
+            .map(|stick| &stick.id)
+    }
+}
+
+pub(crate) fn update_touch_sticks<S: StickIdType>(
+    mut touch_sticks: ResMut<TouchSticks<S>>,
+    sticks: Query<(Entity, &TouchStick<S>)>,
+    mut stick_events: EventReader<TouchStickEvent<S>>,
+) {
+    touch_sticks.sticks.clear();
+    for (entity, stick) in &sticks {
+        touch_sticks.sticks.push(StickStatus {
+            id: stick.id.clone(),
+            entity,
+            pressed: stick.drag_id.is_some(),
+            just_pressed: false,
+            just_released: false,
+            value: stick.value,
+        });
+    }
+
+    for event in stick_events.read() {
+        let Some(status) = touch_sticks
+            .sticks
+            .iter_mut()
+            .find(|stick| stick.entity == event.entity())
+        else {
+            continue;
+        };
+        match event.get_type() {
+            TouchStickEventType::Press => status.just_pressed = true,
+            TouchStickEventType::Release => status.just_released = true,
+            TouchStickEventType::Drag => {}
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::f32::consts::{FRAC_PI_4, PI};
+
+use bevy::{input::ButtonState, prelude::*};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{StickIdType, TouchStick};
+
+/// Turns pushing a [`TouchStick`] in a direction into virtual buttons
+///
+/// Add to a [`TouchStick`]. Each zone is pressed when the stick value points
+/// into it far enough, and sends a [`TouchStickTriggerEvent`] whenever it is
+/// pressed or released. With the `gamepad_mapping` feature, zones can also
+/// press a button of the emulated gamepad.
+#[derive(Component, Clone, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickTriggers {
+    /// The trigger zones, referred to by index in [`TouchStickTriggerEvent`]s
+    pub zones: Vec<TouchStickTriggerZone>,
+}
+
+impl TouchStickTriggers {
+    /// Triggers with the given zones
+    pub fn new(zones: impl IntoIterator<Item = TouchStickTriggerZone>) -> Self {
+        Self {
+            zones: zones.into_iter().collect(),
+        }
+    }
+
+    /// Adds a zone
+    pub fn with_zone(mut self, zone: TouchStickTriggerZone) -> Self {
+        self.zones.push(zone);
+        self
+    }
+}
+
+/// A direction of a [`TouchStickTriggers`] that acts like a button
+///
+/// The zone is pressed when the value is within `tolerance` of `direction` and
+/// its length reaches `press_threshold`. It is released when the value leaves
+/// the angle or its length drops below `release_threshold`, so a stick held
+/// right at the threshold doesn't flicker.
+#[derive(Clone, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickTriggerZone {
+    /// Direction of the zone, with y up, doesn't need to be normalized
+    pub direction: Vec2,
+    /// Maximum angle between the value and `direction` in radians
+    pub tolerance: f32,
+    /// Length of the value at which the zone is pressed
+    pub press_threshold: f32,
+    /// Length of the value below which the zone is released again
+    pub release_threshold: f32,
+    /// Button of the emulated gamepad pressed along with the zone
+    #[cfg(feature = "gamepad_mapping")]
+    pub gamepad_button: Option<GamepadButtonType>,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pressed: bool,
+}
+
+impl Default for TouchStickTriggerZone {
+    fn default() -> Self {
+        Self::new(Vec2::Y)
+    }
+}
+
+impl TouchStickTriggerZone {
+    /// A zone in `direction`, covering a quarter circle and pressed near the edge
+    pub fn new(direction: Vec2) -> Self {
+        Self {
+            direction,
+            tolerance: FRAC_PI_4,
+            press_threshold: 0.9,
+            release_threshold: 0.7,
+            #[cfg(feature = "gamepad_mapping")]
+            gamepad_button: None,
+            pressed: false,
+        }
+    }
+
+    /// A zone pushing the stick up
+    pub fn up() -> Self {
+        Self::new(Vec2::Y)
+    }
+
+    /// A zone pushing the stick down
+    pub fn down() -> Self {
+        Self::new(Vec2::NEG_Y)
+    }
+
+    /// A zone pushing the stick left
+    pub fn left() -> Self {
+        Self::new(Vec2::NEG_X)
+    }
+
+    /// A zone pushing the stick right
+    pub fn right() -> Self {
+        Self::new(Vec2::X)
+    }
+
+    /// Sets the maximum angle from `direction`, clamped to `0.0..=PI`
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance.clamp(0., PI);
+        self
+    }
+
+    /// Sets the press and release thresholds, `release` is capped at `press`
+    pub fn with_thresholds(mut self, press: f32, release: f32) -> Self {
+        self.press_threshold = press;
+        self.release_threshold = release.min(press);
+        self
+    }
+
+    /// Presses `button` on the emulated gamepad along with the zone
+    #[cfg(feature = "gamepad_mapping")]
+    pub fn with_gamepad_button(mut self, button: GamepadButtonType) -> Self {
+        self.gamepad_button = Some(button);
+        self
+    }
+
+    /// Whether the zone is pressed
+    pub fn pressed(&self) -> bool {
+        self.pressed
+    }
+
+    /// Whether `value` points into the zone, ignoring its length
+    fn contains_direction(&self, value: Vec2) -> bool {
+        if value == Vec2::ZERO || self.direction == Vec2::ZERO {
+            return false;
+        }
+        self.direction.angle_between(value).abs() <= self.tolerance
+    }
+
+    fn update(&mut self, value: Vec2) -> bool {
+        let threshold = if self.pressed {
+            self.release_threshold
+        } else {
+            self.press_threshold
+        };
+        let pressed = self.contains_direction(value) && value.length() >= threshold;
+        let changed = pressed != self.pressed;
+        self.pressed = pressed;
+        changed
+    }
+}
+
+/// Sent when a zone of [`TouchStickTriggers`] is pressed or released
+#[derive(Event, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickTriggerEvent<S: StickIdType = ()> {
+    /// Id of the [`TouchStick`]
+    pub id: S,
+    /// Entity holding the [`TouchStick`]
+    pub entity: Entity,
+    /// Index of the zone in [`TouchStickTriggers::zones`]
+    pub zone: usize,
+    /// Whether the zone was pressed or released
+    pub state: ButtonState,
+}
+
+pub(crate) fn update_stick_triggers<S: StickIdType>(
+    mut sticks: Query<(Entity, &TouchStick<S>, &mut TouchStickTriggers)>,
+    mut trigger_events: EventWriter<TouchStickTriggerEvent<S>>,
+) {
+    for (entity, stick, mut triggers) in &mut sticks {
+        let value = stick.value;
+        // avoid change detection when no zone changes
+        let changed = triggers
+            .bypass_change_detection()
+            .zones
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, zone)| zone.update(value).then_some((index, zone.pressed)))
+            .collect::<Vec<_>>();
+
+        if !changed.is_empty() {
+            triggers.set_changed();
+        }
+        for (zone, pressed) in changed {
+            trigger_events.send(TouchStickTriggerEvent {
+                id: stick.id.clone(),
+                entity,
+                zone,
+                state: if pressed {
+                    ButtonState::Pressed
+                } else {
+                    ButtonState::Released
+                },
+            });
+        }
+    }
+}
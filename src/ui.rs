@@ -1,30 +1,197 @@
-use crate::{StickIdType, TouchStick, TouchStickType};
+use crate::{StickIdType, TouchSafeAreaAnchor, TouchStick, TouchStickDisabled, TouchStickType};
 use bevy::{
     prelude::*,
     render::{Extract, RenderApp},
-    ui::{ContentSize, ExtractedUiNodes, FocusPolicy, RelativeCursorPosition, RenderUiSystem},
+    ui::{
+        ContentSize, ExtractedUiNode, ExtractedUiNodes, FocusPolicy, RelativeCursorPosition,
+        RenderUiSystem,
+    },
 };
-use std::marker::PhantomData;
+use std::{f32::consts::TAU, marker::PhantomData};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Marker component for a `bevy_ui` Node area where sticks can be interacted with.
 #[derive(Component, Copy, Clone, Debug, Default, Reflect)]
 #[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TouchStickInteractionArea;
 
 /// Marker component
 #[derive(Component, Copy, Clone, Debug, Default, Reflect)]
 #[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TouchStickUiKnob;
 
 /// Marker component
 #[derive(Component, Copy, Clone, Debug, Default, Reflect)]
 #[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TouchStickUiOutline;
 
+/// Child of a [`TouchStick`] node pointing in the direction the stick is pushed
+///
+/// The image should point along the positive x axis, e.g. an arrow or wedge on
+/// the right edge of the outline. It is centered on the outline, rotated to
+/// the angle of [`TouchStick::value`] and faded by its magnitude.
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickUiDirectionIndicator {
+    /// Snap to this many equally sized sectors, `0` rotates freely
+    ///
+    /// Use with a wedge image covering one sector to highlight the active
+    /// sector of a digital (e.g. 4 or 8 way) stick.
+    pub sectors: u32,
+}
+
+/// Colors applied to the [`TouchStickUiKnob`] and [`TouchStickUiOutline`] of a stick
+///
+/// Add to a [`TouchStick`] node. The colors are multiplied with the colors of
+/// the knob and outline nodes.
+#[derive(Component, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickUiStyle {
+    /// Color while the stick is not pressed
+    pub idle_color: Color,
+    /// Color while the stick is pressed
+    pub pressed_color: Color,
+    /// Color while the stick has [`TouchStickDisabled`]
+    pub disabled_color: Color,
+    /// Seconds without input before an idle stick starts fading out
+    pub fade_delay: f32,
+    /// Seconds the fade out takes
+    pub fade_duration: f32,
+    /// Alpha multiplier of a fully faded out stick
+    pub faded_alpha: f32,
+}
+
+impl Default for TouchStickUiStyle {
+    fn default() -> Self {
+        Self {
+            idle_color: Color::rgba(1., 1., 1., 0.5),
+            pressed_color: Color::WHITE,
+            disabled_color: Color::rgba(0.5, 0.5, 0.5, 0.3),
+            fade_delay: 3.,
+            fade_duration: 1.,
+            faded_alpha: 0.2,
+        }
+    }
+}
+
+/// How the [`TouchStickUiKnob`] of a stick moves with deflection
+///
+/// Add to a [`TouchStick`] node.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickUiKnobMotion {
+    /// How far the knob travels at full deflection, in logical pixels
+    ///
+    /// Uses [`TouchStick::radius`] when `None`.
+    pub travel_radius: Option<f32>,
+    /// Knob scale at full deflection, the knob has scale 1 at rest
+    pub max_scale: f32,
+    /// Rotate the knob so its x axis points in the direction of the stick
+    pub rotate: bool,
+    /// Keep the knob within the bounds of the [`TouchStickUiOutline`] image
+    pub clamp_to_outline: bool,
+}
+
+impl Default for TouchStickUiKnobMotion {
+    fn default() -> Self {
+        Self {
+            travel_radius: None,
+            max_scale: 1.,
+            rotate: false,
+            clamp_to_outline: true,
+        }
+    }
+}
+
+/// Opacity of the knob, outline and direction indicator of a stick
+///
+/// Add to a [`TouchStick`] node. Multiplied with all other colors, e.g. for
+/// a player setting.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickUiOpacity(pub f32);
+
+impl Default for TouchStickUiOpacity {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
+/// Idle presentation of a stick, mostly useful for [`TouchStickType::Floating`]
+///
+/// Add to a [`TouchStick`] node.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TouchStickUiFloatingHint {
+    /// Background color of the interaction area, hinting where the stick can be used
+    ///
+    /// Replaces the [`BackgroundColor`] of the node while set. The previous
+    /// color is restored when it is unset or the hint is removed.
+    pub area_color: Option<Color>,
+    /// Hide the [`TouchStickUiKnob`] and [`TouchStickUiOutline`] while the stick is idle
+    pub hide_when_idle: bool,
+    /// Seconds to keep showing a "ghost" stick where it was last released
+    pub ghost_duration: f32,
+    /// Alpha multiplier of the ghost stick, it fades out from here
+    pub ghost_alpha: f32,
+}
+
+impl Default for TouchStickUiFloatingHint {
+    fn default() -> Self {
+        Self {
+            area_color: None,
+            hide_when_idle: true,
+            ghost_duration: 0.5,
+            ghost_alpha: 0.5,
+        }
+    }
+}
+
+/// How an idle stick with [`TouchStickUiFloatingHint`] is currently drawn
+#[derive(Clone, Copy, Debug, Default)]
+enum IdlePresentation {
+    /// At the regular position
+    #[default]
+    Normal,
+    /// At the position of the last release
+    Ghost { position: Vec2, alpha: f32 },
+    /// Not at all
+    Hidden,
+}
+
+/// Ghost state of a stick with [`TouchStickUiFloatingHint`]
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub(crate) struct TouchStickUiGhost {
+    last_position: Option<Vec2>,
+    remaining_seconds: f32,
+    presentation: IdlePresentation,
+}
+
+/// Background of a stick node before [`TouchStickUiFloatingHint::area_color`] replaced it
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct TouchStickUiReplacedBackground(Option<Color>);
+
+/// Current color of a stick with [`TouchStickUiStyle`]
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct TouchStickUiTint {
+    color: Color,
+    idle_seconds: f32,
+}
+
 // TODO: default returns a broken bundle, should remove or fix
 /// Touch stick ui bundle for easy spawning
 #[derive(Bundle, Debug, Default)]
-pub struct TouchStickUiBundle<S: StickIdType> {
+pub struct TouchStickUiBundle<S: StickIdType = ()> {
     /// Data describing the [`TouchStick`] state
     pub stick: TouchStick<S>,
     /// Where this node will accept touch input
@@ -51,6 +218,8 @@ pub struct TouchStickUiBundle<S: StickIdType> {
     pub z_index: ZIndex,
     /// Cursor position relative to the [`TouchStick`] in normalized logical pixels
     pub cursor_pos: RelativeCursorPosition,
+    /// Positions the node relative to the [`TouchSafeArea`](crate::TouchSafeArea)
+    pub safe_area_anchor: TouchSafeAreaAnchor,
 }
 
 pub(crate) struct TouchStickUiPlugin<S: StickIdType> {
@@ -65,6 +234,20 @@ impl<S: StickIdType> Default for TouchStickUiPlugin<S> {
 
 impl<S: StickIdType> Plugin for TouchStickUiPlugin<S> {
     fn build(&self, app: &mut App) {
+        app.register_type::<TouchStickUiDirectionIndicator>()
+            .register_type::<TouchStickUiStyle>()
+            .register_type::<TouchStickUiKnobMotion>()
+            .register_type::<TouchStickUiFloatingHint>()
+            .register_type::<TouchStickUiOpacity>()
+            .add_systems(
+                PostUpdate,
+                (
+                    update_stick_tint::<S>,
+                    update_stick_ghost::<S>,
+                    restore_stick_background,
+                ),
+            );
+
         let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
         };
@@ -75,51 +258,310 @@ impl<S: StickIdType> Plugin for TouchStickUiPlugin<S> {
     }
 }
 
+#[allow(clippy::type_complexity)]
+fn update_stick_tint<S: StickIdType>(
+    mut commands: Commands,
+    mut sticks: Query<(
+        Entity,
+        &TouchStick<S>,
+        &TouchStickUiStyle,
+        Has<TouchStickDisabled>,
+        Option<&mut TouchStickUiTint>,
+    )>,
+    time: Res<Time>,
+) {
+    for (entity, stick, style, disabled, tint) in &mut sticks {
+        let mut idle_seconds = tint.as_ref().map_or(0., |tint| tint.idle_seconds);
+
+        let color = if disabled {
+            style.disabled_color
+        } else if stick.drag_id.is_some() {
+            idle_seconds = 0.;
+            style.pressed_color
+        } else {
+            idle_seconds += time.delta_seconds();
+            let fade = if style.fade_duration > 0. {
+                ((idle_seconds - style.fade_delay) / style.fade_duration).clamp(0., 1.)
+            } else if idle_seconds >= style.fade_delay {
+                1.
+            } else {
+                0.
+            };
+            let alpha_factor = 1. - fade * (1. - style.faded_alpha);
+            let color = style.idle_color;
+            color.with_a(color.a() * alpha_factor)
+        };
+
+        let new_tint = TouchStickUiTint {
+            color,
+            idle_seconds,
+        };
+        match tint {
+            Some(mut tint) => *tint = new_tint,
+            None => {
+                commands.entity(entity).insert(new_tint);
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_stick_ghost<S: StickIdType>(
+    mut commands: Commands,
+    mut sticks: Query<(
+        Entity,
+        &TouchStick<S>,
+        &TouchStickUiFloatingHint,
+        Option<&mut BackgroundColor>,
+        Option<&TouchStickUiReplacedBackground>,
+        Option<&mut TouchStickUiGhost>,
+    )>,
+    time: Res<Time>,
+) {
+    for (entity, stick, hint, background, replaced, ghost) in &mut sticks {
+        match (hint.area_color, replaced) {
+            (Some(area_color), replaced) => {
+                if replaced.is_none() {
+                    let previous = background.as_ref().map(|background| background.0);
+                    commands
+                        .entity(entity)
+                        .insert(TouchStickUiReplacedBackground(previous));
+                }
+                match background {
+                    Some(mut background) => {
+                        if background.0 != area_color {
+                            background.0 = area_color;
+                        }
+                    }
+                    None => {
+                        commands.entity(entity).insert(BackgroundColor(area_color));
+                    }
+                }
+            }
+            (None, Some(replaced)) => {
+                restore_background(&mut commands, entity, background, replaced);
+            }
+            (None, None) => {}
+        }
+
+        let mut new_ghost = ghost.as_deref().copied().unwrap_or_default();
+
+        if stick.drag_id.is_some() {
+            new_ghost.last_position = match stick.stick_type {
+                TouchStickType::Fixed => None,
+                TouchStickType::Floating => Some(stick.drag_start),
+                TouchStickType::Dynamic => {
+                    Some(stick.base_position).filter(|position| *position != Vec2::ZERO)
+                }
+            };
+            new_ghost.remaining_seconds = hint.ghost_duration;
+            new_ghost.presentation = IdlePresentation::Normal;
+        } else {
+            new_ghost.remaining_seconds =
+                (new_ghost.remaining_seconds - time.delta_seconds()).max(0.);
+            new_ghost.presentation = match new_ghost.last_position {
+                Some(position) if new_ghost.remaining_seconds > 0. => IdlePresentation::Ghost {
+                    position,
+                    alpha: hint.ghost_alpha * new_ghost.remaining_seconds / hint.ghost_duration,
+                },
+                _ if hint.hide_when_idle => IdlePresentation::Hidden,
+                _ => IdlePresentation::Normal,
+            };
+        }
+
+        match ghost {
+            Some(mut ghost) => *ghost = new_ghost,
+            None => {
+                commands.entity(entity).insert(new_ghost);
+            }
+        }
+    }
+}
+
+/// Restores the background of nodes whose [`TouchStickUiFloatingHint`] was removed
+fn restore_stick_background(
+    mut commands: Commands,
+    mut nodes: Query<
+        (
+            Entity,
+            Option<&mut BackgroundColor>,
+            &TouchStickUiReplacedBackground,
+        ),
+        Without<TouchStickUiFloatingHint>,
+    >,
+) {
+    for (entity, background, replaced) in &mut nodes {
+        restore_background(&mut commands, entity, background, replaced);
+    }
+}
+
+fn restore_background(
+    commands: &mut Commands,
+    entity: Entity,
+    background: Option<Mut<BackgroundColor>>,
+    replaced: &TouchStickUiReplacedBackground,
+) {
+    match (background, replaced.0) {
+        (Some(mut background), Some(color)) => background.0 = color,
+        (Some(_), None) => {
+            commands.entity(entity).remove::<BackgroundColor>();
+        }
+        (None, Some(color)) => {
+            commands.entity(entity).insert(BackgroundColor(color));
+        }
+        (None, None) => {}
+    }
+    commands
+        .entity(entity)
+        .remove::<TouchStickUiReplacedBackground>();
+}
+
 #[allow(clippy::type_complexity)]
 pub(crate) fn patch_stick_node<S: StickIdType>(
     mut extracted_uinodes: ResMut<ExtractedUiNodes>,
-    uinode_query: Extract<Query<(&Node, &GlobalTransform, &TouchStick<S>, &ViewVisibility)>>,
-    knob_ui_query: Extract<Query<(Entity, &Parent), With<TouchStickUiKnob>>>,
-    outline_ui_query: Extract<Query<(Entity, &Parent), With<TouchStickUiOutline>>>,
+    uinode_query: Extract<
+        Query<(
+            &Node,
+            &GlobalTransform,
+            &TouchStick<S>,
+            &ViewVisibility,
+            Option<&TouchStickUiTint>,
+            Option<&TouchStickUiKnobMotion>,
+            Option<&TouchStickUiGhost>,
+            Option<&TouchStickUiOpacity>,
+        )>,
+    >,
+    knob_ui_query: Extract<Query<(Entity, &Parent, &Node), With<TouchStickUiKnob>>>,
+    outline_ui_query: Extract<Query<(Entity, &Parent, &Node), With<TouchStickUiOutline>>>,
+    indicator_ui_query: Extract<Query<(Entity, &Parent, &TouchStickUiDirectionIndicator)>>,
 ) {
-    for (knob_entity, knob_parent) in &knob_ui_query {
-        if let Ok((uinode, global_transform, stick, visibility)) = uinode_query.get(**knob_parent) {
+    for (knob_entity, knob_parent, knob_node) in &knob_ui_query {
+        if let Ok((uinode, global_transform, stick, visibility, tint, motion, ghost, opacity)) =
+            uinode_query.get(**knob_parent)
+        {
             if visibility.get() && uinode.size().x != 0. && uinode.size().y != 0. {
-                let radius = stick.radius;
                 let axis_value = stick.value;
+                let mut radius = stick.radius;
+                let mut scale = 1.;
+                let mut rotation = Quat::IDENTITY;
+
+                if let Some(motion) = motion {
+                    scale += (motion.max_scale - 1.) * axis_value.length();
+                    radius = motion.travel_radius.unwrap_or(radius);
+                    if motion.clamp_to_outline {
+                        let outline = outline_ui_query.iter().find(|(_, outline_parent, _)| {
+                            outline_parent.get() == knob_parent.get()
+                        });
+                        if let Some((_, _, outline_node)) = outline {
+                            let max_travel = (outline_node.size() - knob_node.size() * scale) / 2.;
+                            radius = radius.min(max_travel.min_element().max(0.));
+                        }
+                    }
+                    if motion.rotate && axis_value != Vec2::ZERO {
+                        // ui is y down, so we flip
+                        rotation = Quat::from_rotation_z(f32::atan2(-axis_value.y, axis_value.x));
+                    }
+                }
+
                 // ui is y down, so we flip
                 let pos = Vec2::new(axis_value.x, -axis_value.y) * radius;
 
-                let base_pos = get_base_pos(uinode, stick, global_transform);
+                let (base_pos, alpha) = match ghost.map(|ghost| ghost.presentation) {
+                    Some(IdlePresentation::Hidden) => {
+                        extracted_uinodes.uinodes.remove(&knob_entity);
+                        continue;
+                    }
+                    Some(IdlePresentation::Ghost { position, alpha }) => {
+                        (position.extend(0.), alpha)
+                    }
+                    _ => (get_base_pos(uinode, stick, global_transform), 1.),
+                };
                 let knob_pos = base_pos + pos.extend(0.);
 
                 extracted_uinodes
                     .uinodes
                     .entry(knob_entity)
                     .and_modify(|node| {
-                        node.transform = Mat4::from_translation(knob_pos);
+                        node.transform = Mat4::from_scale_rotation_translation(
+                            Vec3::new(scale, scale, 1.),
+                            rotation,
+                            knob_pos,
+                        );
+                        apply_tint(node, tint);
+                        node.color
+                            .set_a(node.color.a() * alpha * opacity_of(opacity));
                     });
             }
         }
     }
 
-    for (outline_entity, outline_parent) in &outline_ui_query {
-        if let Ok((uinode, global_transform, stick, visibility)) =
+    for (outline_entity, outline_parent, _) in &outline_ui_query {
+        if let Ok((uinode, global_transform, stick, visibility, tint, _, ghost, opacity)) =
             uinode_query.get(**outline_parent)
         {
             if visibility.get() && uinode.size().x != 0. && uinode.size().y != 0. {
-                let pos = get_base_pos(uinode, stick, global_transform);
+                let (pos, alpha) = match ghost.map(|ghost| ghost.presentation) {
+                    Some(IdlePresentation::Hidden) => {
+                        extracted_uinodes.uinodes.remove(&outline_entity);
+                        continue;
+                    }
+                    Some(IdlePresentation::Ghost { position, alpha }) => {
+                        (position.extend(0.), alpha)
+                    }
+                    _ => (get_base_pos(uinode, stick, global_transform), 1.),
+                };
                 extracted_uinodes
                     .uinodes
                     .entry(outline_entity)
                     .and_modify(|node| {
                         node.transform = Mat4::from_translation(pos);
+                        apply_tint(node, tint);
+                        node.color
+                            .set_a(node.color.a() * alpha * opacity_of(opacity));
+                    });
+            }
+        }
+    }
+
+    for (indicator_entity, indicator_parent, indicator) in &indicator_ui_query {
+        if let Ok((uinode, global_transform, stick, visibility, tint, _, _, opacity)) =
+            uinode_query.get(**indicator_parent)
+        {
+            if visibility.get() && uinode.size().x != 0. && uinode.size().y != 0. {
+                let pos = get_base_pos(uinode, stick, global_transform);
+                let magnitude = stick.value.length();
+                // ui is y down, so we flip
+                let mut angle = f32::atan2(-stick.value.y, stick.value.x);
+                if indicator.sectors > 0 {
+                    let sector_angle = TAU / indicator.sectors as f32;
+                    angle = (angle / sector_angle).round() * sector_angle;
+                }
+
+                extracted_uinodes
+                    .uinodes
+                    .entry(indicator_entity)
+                    .and_modify(|node| {
+                        node.transform =
+                            Mat4::from_rotation_translation(Quat::from_rotation_z(angle), pos);
+                        apply_tint(node, tint);
+                        node.color
+                            .set_a(node.color.a() * magnitude * opacity_of(opacity));
                     });
             }
         }
     }
 }
 
+fn apply_tint(node: &mut ExtractedUiNode, tint: Option<&TouchStickUiTint>) {
+    if let Some(tint) = tint {
+        node.color = node.color.as_rgba() * Vec4::from(tint.color.as_rgba_f32());
+    }
+}
+
+fn opacity_of(opacity: Option<&TouchStickUiOpacity>) -> f32 {
+    opacity.map_or(1., |opacity| opacity.0)
+}
+
 fn get_base_pos<S: StickIdType>(
     uinode: &Node,
     stick: &TouchStick<S>,
@@ -152,3 +594,55 @@ fn get_base_pos<S: StickIdType>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{input::InputPlugin, window::PrimaryWindow};
+
+    use super::*;
+    use crate::TouchStickPlugin;
+
+    #[test]
+    fn restores_background_replaced_by_area_color() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            InputPlugin,
+            TouchStickPlugin::<()>::default(),
+        ));
+        app.world.spawn((Window::default(), PrimaryWindow));
+        let stick = app
+            .world
+            .spawn((
+                TouchStick::<()>::default(),
+                BackgroundColor(Color::BLUE),
+                TouchStickUiFloatingHint::default(),
+            ))
+            .id();
+        app.update();
+        assert_eq!(
+            app.world.get::<BackgroundColor>(stick).unwrap().0,
+            Color::BLUE
+        );
+
+        let area_color = Color::rgba(1., 1., 1., 0.05);
+        app.world
+            .get_mut::<TouchStickUiFloatingHint>(stick)
+            .unwrap()
+            .area_color = Some(area_color);
+        app.update();
+        assert_eq!(
+            app.world.get::<BackgroundColor>(stick).unwrap().0,
+            area_color
+        );
+
+        app.world
+            .entity_mut(stick)
+            .remove::<TouchStickUiFloatingHint>();
+        app.update();
+        assert_eq!(
+            app.world.get::<BackgroundColor>(stick).unwrap().0,
+            Color::BLUE
+        );
+    }
+}